@@ -4,9 +4,12 @@
 //! filesystem metadata collection, and the selected output renderer.
 
 use glob::{Pattern, glob};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fs;
-use std::io::{self, IsTerminal, Write};
-use std::path::{Path, PathBuf};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::mem;
+use std::path::{Component, Path, PathBuf};
 
 use crate::Params;
 use crate::cli;
@@ -14,14 +17,25 @@ use crate::platform;
 use crate::settings;
 use crate::structs::FileInfo;
 use crate::utils;
+use crate::utils::dired::DiredWriter;
+use crate::utils::disk_usage::UsageCache;
 use crate::utils::file::{
-    collect_file_info, create_file_info,
+    DirectoryEntryData, collect_file_info_with_usage, create_elided_file_info,
     create_file_info_from_metadata_with_gitignore,
-    create_file_info_with_gitignore, format_path_error,
-    preserve_synthetic_dot_name, sanitize_for_terminal,
-    sanitize_path_for_terminal as display_path,
+    create_file_info_with_usage, create_virtual_file_info, entry_metadata,
+    format_path_error, listed_entry_params, mark_child_counts,
+    mark_mount_point, preserve_synthetic_dot_name, sanitize_for_terminal,
 };
 use crate::utils::gitignore::GitignoreCache;
+use crate::utils::quoting::{quote_name, quote_path};
+use crate::utils::render::{LongFormatStream, UsageName, UsageRow, line_end};
+use crate::utils::walk::{
+    DirectoryReader, ElidedEntries, WalkEntry, WalkError, WalkEvent,
+    WalkOptions, WalkSummary, Walker, elide_entries,
+};
+
+/// Entries below this share of their parent are folded in `--usage` output.
+const MIN_USAGE_SHARE: f64 = 0.01;
 
 #[derive(Debug)]
 pub(crate) struct ListingSection {
@@ -33,6 +47,14 @@ pub(crate) struct ListingSection {
 pub(crate) struct TreeSection {
     pub(crate) header: String,
     pub(crate) entries: Vec<TreeEntry>,
+    pub(crate) summary: WalkSummary,
+}
+
+#[derive(Debug)]
+pub(crate) struct UsageSection {
+    pub(crate) rows: Vec<UsageRow>,
+    /// Exit status owed to problems reported while walking the operand.
+    pub(crate) exit_status: i32,
 }
 
 #[derive(Debug)]
@@ -41,9 +63,21 @@ pub(crate) struct TreeEntry {
     pub(crate) name_prefix: String,
 }
 
-struct RecursiveDirectory {
-    section: ListingSection,
-    children: Vec<PathBuf>,
+/// Output produced while walking tree operands.
+enum TreeOutput {
+    /// A new operand starts, named by its header.
+    Start(String),
+    /// Rows that are ready before the walk continues.
+    Rows(Vec<TreeEntry>),
+    /// Totals for one operand, reported after its last rows.
+    Summary(WalkSummary),
+}
+
+/// Output produced while walking recursive operands.
+enum RecursiveOutput {
+    Section(ListingSection),
+    /// Totals for one operand, reported after its last section.
+    Summary(WalkSummary),
 }
 
 #[derive(Clone, Copy)]
@@ -68,8 +102,40 @@ struct RecursiveListing {
     recursive_targets: Vec<RecursiveTarget>,
 }
 
+/// A `--from-stdin` path and the listed paths below it.
+#[derive(Default)]
+struct PathListNode {
+    /// Children in the order they were first listed.
+    children: Vec<(OsString, PathListNode)>,
+    positions: HashMap<OsString, usize>,
+}
+
+impl PathListNode {
+    fn insert(&mut self, path: &Path) {
+        let mut node = self;
+        for name in path_list_names(path) {
+            node = node.child(name);
+        }
+    }
+
+    fn child(&mut self, name: OsString) -> &mut PathListNode {
+        let index = *self.positions.entry(name.clone()).or_insert_with(|| {
+            self.children.push((name, PathListNode::default()));
+            self.children.len() - 1
+        });
+        &mut self.children[index].1
+    }
+}
+
+/// Exit status for minor problems reported while listing continued.
+pub const EXIT_MINOR_TROUBLE: i32 = 1;
+/// Exit status for serious problems reported while listing continued.
+pub const EXIT_SERIOUS_TROUBLE: i32 = 2;
+
 /// Run `lsplus` using parsed CLI flags and config loaded from disk.
-pub fn run_with_flags(args: cli::Flags) -> io::Result<()> {
+///
+/// Returns the exit status owed to problems reported while listing continued.
+pub fn run_with_flags(args: cli::Flags) -> io::Result<i32> {
     let config = settings::load_config();
     run_with_flags_and_config(args, &config)
 }
@@ -77,24 +143,66 @@ pub fn run_with_flags(args: cli::Flags) -> io::Result<()> {
 /// Run `lsplus` using parsed CLI flags and an explicit config value.
 ///
 /// This is primarily useful in tests and library-style entry points that want
-/// to inject config without relying on filesystem state.
+/// to inject config without relying on filesystem state. On success it returns
+/// `0`, [`EXIT_MINOR_TROUBLE`], or [`EXIT_SERIOUS_TROUBLE`] for the worst
+/// problem reported while listing continued.
 pub fn run_with_flags_and_config(
     args: cli::Flags,
     config: &Params,
-) -> io::Result<()> {
+) -> io::Result<i32> {
     let mut params = Params::merge(&args, config);
     platform::validate_params(&params)?;
+    if params.recursive && params.usage {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--usage and --recursive cannot be used together",
+        ));
+    }
     if params.recursive && params.tree {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
             "--tree and --recursive cannot be used together",
         ));
     }
+    if params.directory && params.tree {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--tree and --directory cannot be used together",
+        ));
+    }
+    if params.directory && params.usage {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--usage and --directory cannot be used together",
+        ));
+    }
+    if params.dired && (params.tree || params.usage) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--dired cannot be used with --tree or --usage",
+        ));
+    }
+    // Like `ls -dR`, directory operands are listed without descending.
+    params.recursive &= !params.directory;
     params.resolve_icon_output(
         io::stdout().is_terminal(),
         platform::stdout_is_regular_file(),
     );
-    utils::color::configure_color_output(&params);
+    params.resolve_hyperlink_output(io::stdout().is_terminal());
+    params.resolve_quoting_output(io::stdout().is_terminal());
+    utils::color::configure_color_output(
+        &mut params,
+        io::stdout().is_terminal(),
+    );
+    if args.from_stdin {
+        let section = build_path_list_section(
+            io::stdin().lock(),
+            args.null_separated,
+            &params,
+        )?;
+        TreeRenderer::new(&params, 1).write_section(section)?;
+        return Ok(0);
+    }
     let patterns = patterns_from_args(args.paths);
 
     run_multi(&patterns, &params)
@@ -109,21 +217,30 @@ pub(crate) fn patterns_from_args(paths: Vec<String>) -> Vec<String> {
     }
 }
 
-fn run_multi(patterns: &[String], params: &Params) -> io::Result<()> {
-    if params.tree {
-        return render_tree_sections(
-            &collect_tree_sections(patterns, params)?,
+fn run_multi(patterns: &[String], params: &Params) -> io::Result<i32> {
+    if params.usage {
+        return render_usage_sections(
+            &collect_usage_sections(patterns, params)?,
             params,
         );
     }
 
+    if params.tree {
+        return render_tree(patterns, params);
+    }
+
+    if params.dired {
+        return render_dired_listing(patterns, params);
+    }
+
     if params.recursive {
         return render_recursive_listing(patterns, params);
     }
 
     let sections = collect_listing_sections(patterns, params)?;
 
-    render_listing_sections(&sections, params)
+    render_listing_sections(&sections, params)?;
+    Ok(0)
 }
 
 fn render_listing_sections(
@@ -132,14 +249,15 @@ fn render_listing_sections(
 ) -> io::Result<()> {
     for (index, section) in sections.iter().enumerate() {
         if index > 0 {
-            writeln!(io::stdout())?;
+            write!(io::stdout(), "{}", line_end(params))?;
         }
 
         if let Some(header) = &section.header {
-            writeln!(
+            write!(
                 io::stdout(),
-                "{}:",
-                utils::render::directory_header_text(header)
+                "{}:{}",
+                utils::render::directory_header_text(header),
+                line_end(params)
             )?;
         }
 
@@ -153,95 +271,263 @@ fn render_listing_sections(
     Ok(())
 }
 
-fn render_tree_sections(
-    sections: &[TreeSection],
-    params: &Params,
-) -> io::Result<()> {
-    let show_headers = sections.len() > 1;
+/// Writes tree output to stdout as it is produced.
+struct TreeRenderer<'a> {
+    params: &'a Params,
+    show_headers: bool,
+    started: bool,
+    rows: Option<LongFormatStream<'a, io::BufWriter<io::Stdout>>>,
+}
+
+impl<'a> TreeRenderer<'a> {
+    fn new(params: &'a Params, operands: usize) -> Self {
+        Self {
+            params,
+            // Classic trees give each operand its own root row instead.
+            show_headers: operands > 1 && !params.tree_style.draws_root(),
+            started: false,
+            rows: None,
+        }
+    }
+
+    fn write(&mut self, output: TreeOutput) -> io::Result<()> {
+        match output {
+            TreeOutput::Start(header) => {
+                if mem::replace(&mut self.started, true) {
+                    write!(io::stdout(), "{}", line_end(self.params))?;
+                }
+                if self.show_headers {
+                    write!(
+                        io::stdout(),
+                        "{}:{}",
+                        utils::render::directory_header_text(&header),
+                        line_end(self.params)
+                    )?;
+                }
+                self.rows = Some(LongFormatStream::new(
+                    self.params,
+                    io::BufWriter::new(io::stdout()),
+                ));
+            }
+            TreeOutput::Rows(entries) => {
+                if let Some(rows) = &mut self.rows {
+                    for entry in &entries {
+                        rows.write_row(&entry.info, &entry.name_prefix)?;
+                    }
+                    // Show finished rows before the walk reads further.
+                    rows.flush()?;
+                }
+            }
+            TreeOutput::Summary(summary) => {
+                if let Some(rows) = self.rows.take() {
+                    rows.finish()?;
+                }
+                if self.params.summary {
+                    render_summary(&summary, self.params)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_section(&mut self, section: TreeSection) -> io::Result<()> {
+        self.write(TreeOutput::Start(section.header))?;
+        self.write(TreeOutput::Rows(section.entries))?;
+        self.write(TreeOutput::Summary(section.summary))
+    }
+}
 
+fn render_tree(patterns: &[String], params: &Params) -> io::Result<i32> {
+    let operands = collect_operands(patterns, params)?;
+    let mut renderer = TreeRenderer::new(params, operands.len());
+    let mut status = 0;
+    for_each_tree_output(&operands, params, &mut |output| {
+        if let TreeOutput::Summary(summary) = &output {
+            status = status.max(walk_exit_status(summary));
+        }
+        renderer.write(output)
+    })?;
+    Ok(status)
+}
+
+fn render_usage_sections(
+    sections: &[UsageSection],
+    params: &Params,
+) -> io::Result<i32> {
     for (index, section) in sections.iter().enumerate() {
         if index > 0 {
-            writeln!(io::stdout())?;
+            write!(io::stdout(), "{}", line_end(params))?;
         }
 
-        if show_headers {
-            writeln!(
-                io::stdout(),
-                "{}:",
-                utils::render::directory_header_text(&section.header)
-            )?;
-        }
-        utils::render::display_long_format_with_name_prefixes(
-            section
-                .entries
-                .iter()
-                .map(|entry| (&entry.info, entry.name_prefix.as_str())),
-            params,
-        )?;
+        utils::render::display_usage_rows(&section.rows, params)?;
     }
 
-    Ok(())
+    Ok(sections
+        .iter()
+        .map(|section| section.exit_status)
+        .max()
+        .unwrap_or(0))
 }
 
 fn render_recursive_listing(
     patterns: &[String],
     params: &Params,
-) -> io::Result<()> {
+) -> io::Result<i32> {
     let mut rendered_section = false;
-    for_each_recursive_listing_section(patterns, params, &mut |section| {
-        render_listing_section(&mut rendered_section, &section, params)
-    })
+    let mut status = 0;
+    for_each_recursive_listing_section(patterns, params, &mut |output| {
+        match output {
+            RecursiveOutput::Section(section) => {
+                render_listing_section(&mut rendered_section, &section, params)
+            }
+            RecursiveOutput::Summary(summary) => {
+                status = status.max(walk_exit_status(&summary));
+                if params.summary {
+                    render_summary(&summary, params)?;
+                }
+                Ok(())
+            }
+        }
+    })?;
+    Ok(status)
 }
 
-fn walk_recursive_directory(
-    path: &Path,
+/// Render long-format sections followed by their dired offsets.
+///
+/// Summaries are left out, since dired reads the listing back line by line.
+fn render_dired_listing(
+    patterns: &[String],
     params: &Params,
-    fail_on_error: bool,
-    visible_entry_depth: usize,
-    mode: RecursiveMode<'_>,
-    sink: &mut impl FnMut(ListingSection) -> io::Result<()>,
-) -> io::Result<bool> {
-    let mut directory = match collect_recursive_directory(
-        path,
-        params,
-        visible_entry_depth > 1,
-        mode.name_filter(),
-    ) {
-        Ok(directory) => directory,
-        Err(err) if fail_on_error => return Err(err),
-        Err(err) => {
-            report_path_error(path, &err);
-            return Ok(false);
+) -> io::Result<i32> {
+    let mut output = DiredWriter::new(io::stdout().lock());
+    let mut rendered_section = false;
+    let mut write_section = |section: &ListingSection| -> io::Result<()> {
+        if rendered_section {
+            writeln!(output)?;
+        }
+        if let Some(header) = &section.header {
+            output.write_header(header)?;
         }
+        utils::render::write_long_format(
+            &mut output,
+            &section.entries,
+            params,
+        )?;
+        rendered_section = true;
+        Ok(())
     };
 
-    directory.section.header =
-        recursive_section_header(path, visible_entry_depth);
-    let mut found_match = !directory.section.entries.is_empty();
-    if mode.emit_empty_sections() || found_match {
-        sink(directory.section)?;
+    let mut status = 0;
+    if params.recursive {
+        for_each_recursive_listing_section(patterns, params, &mut |output| {
+            match output {
+                RecursiveOutput::Section(section) => write_section(&section),
+                RecursiveOutput::Summary(summary) => {
+                    status = status.max(walk_exit_status(&summary));
+                    Ok(())
+                }
+            }
+        })?;
+    } else {
+        for section in &collect_listing_sections(patterns, params)? {
+            write_section(section)?;
+        }
     }
 
-    if params
-        .recursive_level
-        .is_some_and(|limit| visible_entry_depth >= limit)
-    {
-        return Ok(found_match);
+    output.finish(params)?;
+    Ok(status)
+}
+
+fn render_summary(summary: &WalkSummary, params: &Params) -> io::Result<()> {
+    let line_end = line_end(params);
+    write!(
+        io::stdout(),
+        "{line_end}{}{line_end}",
+        utils::render::summary_text(summary, params)
+    )
+}
+
+fn walk_recursive_directory(
+    path: &Path,
+    params: &Params,
+    mode: RecursiveMode<'_>,
+    reader: &mut DirectoryReader,
+    usage: &UsageCache,
+    sink: &mut impl FnMut(RecursiveOutput) -> io::Result<()>,
+) -> io::Result<bool> {
+    let mut gitignore_cache = GitignoreCache::default();
+    let mut found_match = false;
+    let mut summary = WalkSummary::default();
+    let mut options = WalkOptions::recursive(params);
+    // Filters pick entries after they are listed, so the limit applies to
+    // matches instead.
+    if mode.name_filter().is_some() {
+        options.max_entries = None;
     }
 
-    for child in directory.children {
-        if walk_recursive_directory(
-            &child,
-            params,
-            false,
-            visible_entry_depth + 1,
-            mode,
-            sink,
-        )? {
-            found_match = true;
+    for event in Walker::new(path, params, options, reader) {
+        match event {
+            WalkEvent::Enter(directory) => {
+                let mut shown = directory
+                    .entries
+                    .into_iter()
+                    .filter(|entry| {
+                        mode.name_filter()
+                            .is_none_or(|pattern| pattern.matches(&entry.name))
+                    })
+                    .collect::<Vec<_>>();
+                let elided = directory.elided.or_else(|| {
+                    elide_entries(
+                        &mut shown,
+                        params.max_entries,
+                        params,
+                        usage,
+                    )
+                });
+                let mut entries = shown
+                    .iter()
+                    .map(|entry| {
+                        summary.count_entry(entry);
+                        walk_entry_file_info(
+                            entry,
+                            params,
+                            &mut gitignore_cache,
+                            usage,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(elided) = elided {
+                    entries.push(create_elided_file_info(
+                        &directory.path,
+                        elided,
+                    ));
+                }
+
+                found_match |= !entries.is_empty();
+                if mode.emit_empty_sections() || !entries.is_empty() {
+                    sink(RecursiveOutput::Section(ListingSection {
+                        header: recursive_section_header(
+                            &directory.path,
+                            directory.depth,
+                            params,
+                        ),
+                        entries,
+                    }))?;
+                }
+            }
+            WalkEvent::Leave => {}
+            WalkEvent::DirectoryError(error) if error.root => {
+                return Err(error.error);
+            }
+            WalkEvent::DirectoryError(error)
+            | WalkEvent::EntryError(error) => {
+                report_walk_error(&error, &mut summary);
+            }
         }
     }
 
+    sink(RecursiveOutput::Summary(summary))?;
     Ok(found_match)
 }
 
@@ -251,14 +537,15 @@ fn render_listing_section(
     params: &Params,
 ) -> io::Result<()> {
     if *rendered_section {
-        writeln!(io::stdout())?;
+        write!(io::stdout(), "{}", line_end(params))?;
     }
 
     if let Some(header) = &section.header {
-        writeln!(
+        write!(
             io::stdout(),
-            "{}:",
-            utils::render::directory_header_text(header)
+            "{}:{}",
+            utils::render::directory_header_text(header),
+            line_end(params)
         )?;
     }
 
@@ -282,15 +569,53 @@ pub(crate) fn collect_listing_sections(
     }
 
     let operands = collect_operands(patterns, params)?;
-    build_listing_sections(&operands, params)
+    build_listing_sections(&operands, params, &UsageCache::default())
 }
 
+/// Collect tree sections in memory; normal output streams them instead.
+#[cfg(test)]
 pub(crate) fn collect_tree_sections(
     patterns: &[String],
     params: &Params,
 ) -> io::Result<Vec<TreeSection>> {
     let operands = collect_operands(patterns, params)?;
-    Ok(build_tree_sections(&operands, params))
+    let mut sections: Vec<TreeSection> = Vec::new();
+    for_each_tree_output(&operands, params, &mut |output| {
+        match output {
+            TreeOutput::Start(header) => sections.push(TreeSection {
+                header,
+                entries: Vec::new(),
+                summary: WalkSummary::default(),
+            }),
+            TreeOutput::Rows(rows) => {
+                if let Some(section) = sections.last_mut() {
+                    section.entries.extend(rows);
+                }
+            }
+            TreeOutput::Summary(summary) => {
+                if let Some(section) = sections.last_mut() {
+                    section.summary = summary;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(sections)
+}
+
+pub(crate) fn collect_usage_sections(
+    patterns: &[String],
+    params: &Params,
+) -> io::Result<Vec<UsageSection>> {
+    let operands = collect_operands(patterns, params)?;
+    let usage = UsageCache::default();
+    let mut reader = DirectoryReader::new(params, &usage);
+    Ok(operands
+        .iter()
+        .filter_map(|path| {
+            build_usage_section(path, params, &mut reader, &usage)
+        })
+        .collect())
 }
 
 fn collect_operands(
@@ -315,8 +640,10 @@ fn collect_recursive_listing_sections(
     params: &Params,
 ) -> io::Result<Vec<ListingSection>> {
     let mut sections = Vec::new();
-    for_each_recursive_listing_section(patterns, params, &mut |section| {
-        sections.push(section);
+    for_each_recursive_listing_section(patterns, params, &mut |output| {
+        if let RecursiveOutput::Section(section) = output {
+            sections.push(section);
+        }
         Ok(())
     })?;
 
@@ -326,19 +653,22 @@ fn collect_recursive_listing_sections(
 fn for_each_recursive_listing_section(
     patterns: &[String],
     params: &Params,
-    sink: &mut impl FnMut(ListingSection) -> io::Result<()>,
+    sink: &mut impl FnMut(RecursiveOutput) -> io::Result<()>,
 ) -> io::Result<()> {
-    let listing = prepare_recursive_listing(patterns, params)?;
+    let usage = UsageCache::default();
+    let listing = prepare_recursive_listing(patterns, params, &usage)?;
     if !listing.file_entries.is_empty() {
-        sink(ListingSection {
+        sink(RecursiveOutput::Section(ListingSection {
             header: None,
             entries: listing.file_entries,
-        })?;
+        }))?;
     }
 
     let mut first_error = None;
+    let mut reader = DirectoryReader::new(params, &usage);
     for target in listing.recursive_targets {
-        let result = walk_recursive_target(target, params, sink);
+        let result =
+            walk_recursive_target(target, params, &mut reader, &usage, sink);
 
         if let Err(err) = result
             && first_error.is_none()
@@ -357,6 +687,7 @@ fn for_each_recursive_listing_section(
 fn prepare_recursive_listing(
     patterns: &[String],
     params: &Params,
+    usage: &UsageCache,
 ) -> io::Result<RecursiveListing> {
     let targets = collect_recursive_targets(patterns, params)?;
     let mut file_entries = Vec::new();
@@ -368,7 +699,8 @@ fn prepare_recursive_listing(
                 recursive_targets.push(RecursiveTarget::Path(path));
             }
             RecursiveTarget::Path(path) => {
-                file_entries.push(create_file_info(&path, params)?);
+                file_entries
+                    .push(create_file_info_with_usage(&path, params, usage)?);
             }
             RecursiveTarget::Filter(filter) => {
                 recursive_targets.push(RecursiveTarget::Filter(filter));
@@ -385,17 +717,21 @@ fn prepare_recursive_listing(
 fn walk_recursive_target(
     target: RecursiveTarget,
     params: &Params,
-    sink: &mut impl FnMut(ListingSection) -> io::Result<()>,
+    reader: &mut DirectoryReader,
+    usage: &UsageCache,
+    sink: &mut impl FnMut(RecursiveOutput) -> io::Result<()>,
 ) -> io::Result<()> {
     match target {
-        RecursiveTarget::Path(path) => walk_recursive_directory(
-            &path,
-            params,
-            true,
-            1,
-            RecursiveMode::Normal,
-            sink,
-        )
+        RecursiveTarget::Path(path) => {
+            walk_recursive_directory(
+                &path,
+                params,
+                RecursiveMode::Normal,
+                reader,
+                usage,
+                sink,
+            )
+        }
         .map(|_| ())
         .inspect_err(|err| report_path_error(&path, err)),
         RecursiveTarget::Filter(filter) => {
@@ -410,9 +746,9 @@ fn walk_recursive_target(
             walk_recursive_directory(
                 &filter.root,
                 params,
-                true,
-                1,
                 RecursiveMode::Filter(&filter.pattern),
+                reader,
+                usage,
                 sink,
             )
             .map(|found_match| {
@@ -562,9 +898,10 @@ fn append_pattern_operands(
 fn build_listing_sections(
     operands: &[PathBuf],
     params: &Params,
+    usage: &UsageCache,
 ) -> io::Result<Vec<ListingSection>> {
     let (file_entries, directory_operands) =
-        split_file_and_directory_operands(operands, params)?;
+        split_file_and_directory_operands(operands, params, usage)?;
 
     let show_directory_headers =
         !file_entries.is_empty() || directory_operands.len() > 1;
@@ -579,8 +916,8 @@ fn build_listing_sections(
 
     for path in directory_operands {
         sections.push(ListingSection {
-            header: show_directory_headers.then(|| display_path(path)),
-            entries: collect_file_info(path, params)?,
+            header: show_directory_headers.then(|| quote_path(path, params)),
+            entries: collect_file_info_with_usage(path, params, usage)?,
         });
     }
 
@@ -590,195 +927,715 @@ fn build_listing_sections(
 fn split_file_and_directory_operands<'a>(
     operands: &'a [PathBuf],
     params: &Params,
+    usage: &UsageCache,
 ) -> io::Result<(Vec<FileInfo>, Vec<&'a PathBuf>)> {
     let mut file_entries = Vec::new();
     let mut directory_operands = Vec::new();
 
     for path in operands {
-        if is_display_directory(path) {
+        if !params.directory && is_display_directory(path) {
             directory_operands.push(path);
         } else {
-            file_entries.push(create_file_info(path, params)?);
+            file_entries
+                .push(create_file_info_with_usage(path, params, usage)?);
         }
     }
 
     Ok((file_entries, directory_operands))
 }
 
-fn collect_recursive_directory(
+fn for_each_tree_output(
+    operands: &[PathBuf],
+    params: &Params,
+    sink: &mut dyn FnMut(TreeOutput) -> io::Result<()>,
+) -> io::Result<()> {
+    let usage = UsageCache::default();
+    let mut reader = DirectoryReader::new(params, &usage);
+    for path in operands {
+        if is_display_directory(path) {
+            stream_tree_section(path, params, &mut reader, &usage, sink)?;
+            continue;
+        }
+
+        match create_file_info_with_usage(path, params, &usage) {
+            Ok(info) => {
+                let mut summary = WalkSummary::default();
+                if let Ok(metadata) =
+                    entry_metadata(path, params.dereference.follows_operands())
+                {
+                    summary.count(&metadata);
+                }
+                sink(TreeOutput::Start(quote_path(path, params)))?;
+                sink(TreeOutput::Rows(vec![TreeEntry {
+                    info,
+                    name_prefix: String::new(),
+                }]))?;
+                sink(TreeOutput::Summary(summary))?;
+            }
+            Err(err) => report_path_error(path, &err),
+        }
+    }
+
+    Ok(())
+}
+
+/// A directory whose tree rows are still being emitted.
+struct TreeFrame {
+    path: PathBuf,
+    entries: std::iter::Peekable<std::vec::IntoIter<WalkEntry>>,
+    /// Entries left out by `--max-entries`, shown as one final row.
+    elided: Option<ElidedEntries>,
+    depth: usize,
+    /// Prefix drawn before the branch of every row in this directory.
+    ancestor_prefix: String,
+    /// Prefix handed to the directory entered from the last emitted row.
+    child_prefix: String,
+    /// With `--dirs-only`, the row of the directory being entered, held back
+    /// until its listing supplies the child counts.
+    pending_row: Option<TreeEntry>,
+}
+
+/// Walk one directory operand, handing rows to `sink` as they are ready.
+///
+/// Rows are batched per walker event, so each batch ends where the walk has
+/// to read another directory before more rows can follow.
+fn stream_tree_section(
     path: &Path,
     params: &Params,
-    hide_dot_entries: bool,
-    name_filter: Option<&Pattern>,
-) -> io::Result<RecursiveDirectory> {
-    let child_names = utils::file::collect_file_names(path, params)?;
-    let mut entries = Vec::new();
-    let mut children = Vec::new();
+    reader: &mut DirectoryReader,
+    usage: &UsageCache,
+    sink: &mut dyn FnMut(TreeOutput) -> io::Result<()>,
+) -> io::Result<()> {
+    sink(TreeOutput::Start(quote_path(path, params)))?;
+    let mut section = TreeSection {
+        header: quote_path(path, params),
+        entries: Vec::new(),
+        summary: WalkSummary::default(),
+    };
     let mut gitignore_cache = GitignoreCache::default();
+    let mut frames: Vec<TreeFrame> = Vec::new();
+    if params.tree_style.draws_root()
+        && let Ok(info) = create_file_info_with_usage(path, params, usage)
+    {
+        section.entries.push(TreeEntry {
+            info,
+            name_prefix: String::new(),
+        });
+    }
 
-    for child_name in child_names {
-        if hide_dot_entries
-            && (child_name.as_str() == "." || child_name.as_str() == "..")
-        {
-            continue;
-        }
+    let mut options = WalkOptions::tree(params);
+    if params.dirs_only {
+        // Read one level past the deepest row so every shown directory can
+        // report what it holds.
+        options.max_depth = Some(params.tree_level + 1);
+        options.directories_only = true;
+    }
+    let walker = Walker::new(path, params, options, reader);
+    let events: Box<dyn Iterator<Item = WalkEvent>> = if params.prune_empty {
+        Box::new(prune_empty_directories(walker.collect()).into_iter())
+    } else {
+        Box::new(walker)
+    };
 
-        let child_path = path.join(&child_name);
-        let metadata = match fs::symlink_metadata(&child_path) {
-            Ok(metadata) => metadata,
-            Err(err) => {
-                report_path_error(&child_path, &err);
+    for event in events {
+        match event {
+            WalkEvent::Enter(mut directory) => {
+                if let Some(mut row) = frames
+                    .last_mut()
+                    .and_then(|frame| frame.pending_row.take())
+                {
+                    mark_child_counts(
+                        &mut row.info,
+                        directory.counts.files,
+                        directory.counts.directories,
+                    );
+                    section.entries.push(row);
+                }
+                if params.dirs_only && directory.depth > params.tree_level {
+                    directory.entries.clear();
+                    directory.elided = None;
+                }
+                let ancestor_prefix = frames
+                    .last()
+                    .map(|frame| frame.child_prefix.clone())
+                    .unwrap_or_default();
+                frames.push(TreeFrame {
+                    path: directory.path,
+                    entries: directory.entries.into_iter().peekable(),
+                    elided: directory.elided,
+                    depth: directory.depth,
+                    ancestor_prefix,
+                    child_prefix: String::new(),
+                    pending_row: None,
+                });
+            }
+            WalkEvent::Leave => {
+                frames.pop();
+            }
+            WalkEvent::DirectoryError(error) => {
+                if let Some(row) = frames
+                    .last_mut()
+                    .and_then(|frame| frame.pending_row.take())
+                {
+                    section.entries.push(row);
+                }
+                report_walk_error(&error, &mut section.summary);
+            }
+            WalkEvent::EntryError(error) => {
+                report_walk_error(&error, &mut section.summary);
                 continue;
             }
-        };
-        let classification = platform::classify_entry(&child_path, &metadata);
+        }
 
-        if name_filter.is_none_or(|pattern| pattern.matches(&child_name)) {
-            let mut info = create_file_info_from_metadata_with_gitignore(
-                &child_path,
-                &metadata,
-                params,
-                &mut gitignore_cache,
-            );
-            preserve_synthetic_dot_name(&mut info, &child_name);
-            entries.push(info);
+        append_ready_tree_rows(
+            &mut section,
+            &mut frames,
+            params,
+            &mut gitignore_cache,
+            usage,
+        );
+        if !section.entries.is_empty() {
+            sink(TreeOutput::Rows(mem::take(&mut section.entries)))?;
         }
+    }
 
-        if is_traversable_child_name(&child_name)
-            && classification.may_recurse
-            && !should_prune_directory(&child_path, params)
-        {
-            children.push(child_path);
+    sink(TreeOutput::Summary(section.summary))
+}
+
+/// Drop descended directories whose subtree ends up without any rows.
+///
+/// The whole walk is buffered because a directory's row can only be kept once
+/// every directory below it has been read. Directories that were listed but
+/// not descended into, such as pruned ones, are always kept.
+fn prune_empty_directories(events: Vec<WalkEvent>) -> Vec<WalkEvent> {
+    let mut empty = HashSet::new();
+    let mut open = Vec::new();
+
+    for event in &events {
+        match event {
+            WalkEvent::Enter(directory) => open.push(directory),
+            WalkEvent::Leave => {
+                let Some(directory) = open.pop() else {
+                    continue;
+                };
+                if directory.elided.is_none()
+                    && directory.counts.files == 0
+                    && directory.entries.iter().all(|entry| {
+                        entry.descend && empty.contains(&entry.path)
+                    })
+                {
+                    empty.insert(directory.path.clone());
+                }
+            }
+            WalkEvent::DirectoryError(_) | WalkEvent::EntryError(_) => {}
         }
     }
 
-    Ok(RecursiveDirectory {
-        section: ListingSection {
-            header: Some(display_path(path)),
-            entries,
-        },
-        children,
-    })
+    let mut skipped = 0_usize;
+    events
+        .into_iter()
+        .filter_map(|event| match event {
+            WalkEvent::Enter(_) | WalkEvent::Leave if skipped > 0 => {
+                if matches!(event, WalkEvent::Enter(_)) {
+                    skipped += 1;
+                } else {
+                    skipped -= 1;
+                }
+                None
+            }
+            WalkEvent::Enter(directory) if empty.contains(&directory.path) => {
+                skipped += 1;
+                None
+            }
+            WalkEvent::Enter(mut directory) => {
+                directory.entries.retain(|entry| {
+                    !(entry.descend && empty.contains(&entry.path))
+                });
+                Some(WalkEvent::Enter(directory))
+            }
+            event => Some(event),
+        })
+        .collect()
 }
 
-fn build_tree_sections(
-    operands: &[PathBuf],
+/// Emit rows from the innermost directory until the walker must descend.
+///
+/// Rows after a descended entry wait for that subtree, which the walker
+/// reports next, so the rendered order matches a depth-first tree.
+fn append_ready_tree_rows(
+    section: &mut TreeSection,
+    frames: &mut [TreeFrame],
     params: &Params,
-) -> Vec<TreeSection> {
-    let mut sections = Vec::new();
+    gitignore_cache: &mut GitignoreCache,
+    usage: &UsageCache,
+) {
+    let Some(frame) = frames.last_mut() else {
+        return;
+    };
 
-    for path in operands {
-        let mut gitignore_cache = GitignoreCache::default();
-        if is_display_directory(path) {
-            let mut section = TreeSection {
-                header: display_path(path),
-                entries: Vec::new(),
+    let style = params.tree_style;
+    let root_less = frame.depth == 1 && !style.draws_root();
+
+    while let Some(entry) = frame.entries.next() {
+        let is_last = frame.entries.peek().is_none() && frame.elided.is_none();
+        let name_prefix = if root_less {
+            String::new()
+        } else {
+            format!("{}{}", frame.ancestor_prefix, style.branch(is_last))
+        };
+        section.summary.count_entry(&entry);
+        let row = TreeEntry {
+            info: walk_entry_file_info(&entry, params, gitignore_cache, usage),
+            name_prefix,
+        };
+        if entry.descend && params.dirs_only {
+            frame.pending_row = Some(row);
+        } else {
+            section.entries.push(row);
+        }
+
+        if entry.descend {
+            let next_prefix = if root_less {
+                ""
+            } else {
+                style.continuation(is_last)
             };
-            append_tree_entries(
-                &mut section,
-                path,
-                params,
-                &mut gitignore_cache,
-                1,
-                String::new(),
-            );
-            sections.push(section);
+            frame.child_prefix =
+                format!("{}{next_prefix}", frame.ancestor_prefix);
+            return;
+        }
+    }
+
+    if let Some(elided) = frame.elided.take() {
+        let name_prefix = if root_less {
+            String::new()
         } else {
-            match create_file_info_with_gitignore(
-                path,
-                params,
-                &mut gitignore_cache,
-            ) {
-                Ok(info) => sections.push(TreeSection {
-                    header: display_path(path),
-                    entries: vec![TreeEntry {
-                        info,
-                        name_prefix: String::new(),
-                    }],
-                }),
-                Err(err) => report_path_error(path, &err),
+            format!("{}{}", frame.ancestor_prefix, style.branch(true))
+        };
+        section.entries.push(TreeEntry {
+            info: create_elided_file_info(&frame.path, elided),
+            name_prefix,
+        });
+    }
+}
+
+/// Build one tree section from the paths listed on `input`.
+///
+/// Paths are split on newlines, or on NUL bytes with `-0`, and arranged by
+/// their components without reading any directory. Listed paths that exist
+/// are statted for the long-format columns; the rest, and any parents they
+/// imply, are shown as virtual entries.
+pub(crate) fn build_path_list_section(
+    input: impl BufRead,
+    null_separated: bool,
+    params: &Params,
+) -> io::Result<TreeSection> {
+    let separator = if null_separated { b'\0' } else { b'\n' };
+    let mut root = PathListNode::default();
+
+    for line in input.split(separator) {
+        let mut line = line?;
+        if !null_separated && line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if !line.is_empty() {
+            root.insert(&platform::path_from_bytes(line));
+        }
+    }
+
+    let mut section = TreeSection {
+        header: String::from("-"),
+        entries: Vec::new(),
+        summary: WalkSummary::default(),
+    };
+    let usage = UsageCache::default();
+    if params.tree_style.draws_root()
+        && let Ok(info) =
+            create_file_info_with_usage(Path::new("."), params, &usage)
+    {
+        section.entries.push(TreeEntry {
+            info,
+            name_prefix: String::new(),
+        });
+    }
+    append_path_list_rows(
+        &mut section,
+        root,
+        &PathListParent {
+            path: Path::new(""),
+            depth: 1,
+            prefix: "",
+        },
+        params,
+        &mut GitignoreCache::default(),
+        &usage,
+    );
+
+    Ok(section)
+}
+
+/// Split a listed path into the names of its tree rows.
+///
+/// `.` components are dropped, and a root or drive prefix becomes one row.
+fn path_list_names(path: &Path) -> Vec<OsString> {
+    let mut names = Vec::new();
+    let mut anchor = OsString::new();
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => {
+                anchor.push(component.as_os_str());
+            }
+            Component::CurDir => {}
+            Component::ParentDir | Component::Normal(_) => {
+                if !anchor.is_empty() {
+                    names.push(mem::take(&mut anchor));
+                }
+                names.push(component.as_os_str().to_os_string());
             }
         }
     }
+    if !anchor.is_empty() {
+        names.push(anchor);
+    }
 
-    sections
+    names
 }
 
-fn append_tree_entries(
+/// The path-list node whose children are being turned into tree rows.
+struct PathListParent<'a> {
+    path: &'a Path,
+    depth: usize,
+    /// Prefix drawn before the branch of every row in this directory.
+    prefix: &'a str,
+}
+
+fn append_path_list_rows(
     section: &mut TreeSection,
-    directory: &Path,
+    node: PathListNode,
+    parent: &PathListParent<'_>,
     params: &Params,
     gitignore_cache: &mut GitignoreCache,
-    depth: usize,
-    ancestor_prefix: String,
+    usage: &UsageCache,
 ) {
-    let child_names = match utils::file::collect_file_names(directory, params)
-    {
-        Ok(names) => names,
-        Err(err) => {
-            report_path_error(directory, &err);
-            return;
-        }
-    };
+    let style = params.tree_style;
+    let root_less = parent.depth == 1 && !style.draws_root();
+    let mut children =
+        sorted_path_list_children(node, parent.path, params, usage);
+    let elided = params
+        .max_entries
+        .filter(|limit| children.len() > *limit)
+        .map(|limit| {
+            let elided = children.split_off(limit);
+            ElidedEntries {
+                count: elided.len(),
+                bytes: elided
+                    .iter()
+                    .filter_map(|(entry, _)| entry.metadata.as_ref())
+                    .map(fs::Metadata::len)
+                    .sum(),
+            }
+        });
+    let count = children.len() + usize::from(elided.is_some());
 
-    let child_names = traversal_child_names(child_names);
-    let child_count = child_names.len();
-    for (index, child_name) in child_names.iter().enumerate() {
-        let child_path = directory.join(child_name);
-        let is_last = index + 1 == child_count;
-        let branch = if is_last { "└── " } else { "├── " };
-        let name_prefix = if depth == 1 {
+    for (index, (entry, child)) in children.into_iter().enumerate() {
+        let is_last = index + 1 == count;
+        let name_prefix = if root_less {
             String::new()
         } else {
-            format!("{ancestor_prefix}{branch}")
+            format!("{}{}", parent.prefix, style.branch(is_last))
         };
-
-        match create_file_info_with_gitignore(
-            &child_path,
-            params,
-            gitignore_cache,
-        ) {
-            Ok(info) => {
-                section.entries.push(TreeEntry { info, name_prefix });
+        let has_children = !child.children.is_empty();
+        let info = match &entry.metadata {
+            Some(metadata) => {
+                section.summary.count(metadata);
+                create_file_info_from_metadata_with_gitignore(
+                    &entry.path,
+                    metadata,
+                    params,
+                    gitignore_cache,
+                    usage,
+                )
             }
-            Err(err) => {
-                report_path_error(&child_path, &err);
-                continue;
+            None => {
+                if has_children {
+                    section.summary.directories += 1;
+                } else {
+                    section.summary.files += 1;
+                }
+                create_virtual_file_info(&entry.path, has_children, params)
             }
-        }
+        };
+        section.entries.push(TreeEntry { info, name_prefix });
 
-        if depth < params.tree_level
-            && is_recursable_directory(&child_path)
-            && !should_prune_directory(&child_path, params)
-        {
-            let next_prefix = if depth == 1 {
-                ""
-            } else if is_last {
-                "    "
+        if has_children && parent.depth < params.tree_level {
+            let prefix = if root_less {
+                String::new()
             } else {
-                "│   "
+                format!("{}{}", parent.prefix, style.continuation(is_last))
             };
-            append_tree_entries(
+            append_path_list_rows(
                 section,
-                &child_path,
+                child,
+                &PathListParent {
+                    path: &entry.path,
+                    depth: parent.depth + 1,
+                    prefix: &prefix,
+                },
                 params,
                 gitignore_cache,
-                depth + 1,
-                format!("{ancestor_prefix}{next_prefix}"),
+                usage,
             );
         }
     }
+
+    if let Some(elided) = elided {
+        let name_prefix = if root_less {
+            String::new()
+        } else {
+            format!("{}{}", parent.prefix, style.branch(true))
+        };
+        section.entries.push(TreeEntry {
+            info: create_elided_file_info(parent.path, elided),
+            name_prefix,
+        });
+    }
 }
 
-fn traversal_child_names(child_names: Vec<String>) -> Vec<String> {
-    child_names
+/// Order a node's children the way a directory listing would be ordered.
+///
+/// Virtual entries have no metadata, so they sort like entries whose metadata
+/// could not be read; those with listed children still group as directories.
+fn sorted_path_list_children(
+    node: PathListNode,
+    parent: &Path,
+    params: &Params,
+    usage: &UsageCache,
+) -> Vec<(DirectoryEntryData, PathListNode)> {
+    let mut nodes = HashMap::new();
+    let mut entries = Vec::with_capacity(node.children.len());
+
+    for (name, child) in node.children {
+        let path = parent.join(&name);
+        let metadata =
+            entry_metadata(&path, params.dereference.follows_entries()).ok();
+        let classification_result = metadata
+            .as_ref()
+            .map(|metadata| platform::classify_entry(&path, metadata))
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound));
+        entries.push(DirectoryEntryData {
+            file_name: name.clone(),
+            path,
+            metadata,
+            classification_result,
+        });
+        nodes.insert(name, child);
+    }
+
+    utils::sort::sort_entries(&mut entries, params, usage);
+    if params.dirs_first {
+        let (dirs, files): (Vec<_>, Vec<_>) =
+            entries.into_iter().partition(|entry| {
+                !nodes[&entry.file_name].children.is_empty()
+                    || entry.classification_result.as_ref().is_ok_and(
+                        |classification| classification.group_with_directories,
+                    )
+            });
+        entries = dirs.into_iter().chain(files).collect();
+    }
+
+    entries
         .into_iter()
-        .filter(|name| is_traversable_child_name(name))
+        .map(|entry| {
+            let child = nodes.remove(&entry.file_name).unwrap_or_default();
+            (entry, child)
+        })
         .collect()
 }
 
-fn is_traversable_child_name(name: &str) -> bool {
-    name != "." && name != ".."
+/// Build `--usage` rows for one operand, starting with the operand itself.
+///
+/// The walk is collected first because each row needs its parent's total and
+/// whether smaller siblings will be folded after it.
+fn build_usage_section(
+    path: &Path,
+    params: &Params,
+    reader: &mut DirectoryReader,
+    usage: &UsageCache,
+) -> Option<UsageSection> {
+    let root = match create_file_info_with_usage(path, params, usage) {
+        Ok(info) => info,
+        Err(err) => {
+            report_path_error(path, &err);
+            return None;
+        }
+    };
+    let mut listings = HashMap::new();
+    let mut summary = WalkSummary::default();
+
+    if is_display_directory(path) {
+        // Shares are only meaningful when every entry is listed.
+        let options = WalkOptions {
+            max_entries: None,
+            ..WalkOptions::tree(params)
+        };
+        for event in Walker::new(path, params, options, reader) {
+            match event {
+                WalkEvent::Enter(directory) => {
+                    listings.insert(directory.path, directory.entries);
+                }
+                WalkEvent::Leave => {}
+                WalkEvent::DirectoryError(error)
+                | WalkEvent::EntryError(error) => {
+                    report_walk_error(&error, &mut summary);
+                }
+            }
+        }
+    }
+
+    let root_size = root.size;
+    let mut rows = vec![UsageRow {
+        name: UsageName::Entry(Box::new(root)),
+        name_prefix: String::new(),
+        size: root_size,
+        share: 1.0,
+    }];
+    append_usage_rows(
+        &mut rows,
+        &mut listings,
+        &UsageParent {
+            path,
+            size: root_size,
+            prefix: "",
+        },
+        params,
+        &mut GitignoreCache::default(),
+        usage,
+    );
+
+    Some(UsageSection {
+        rows,
+        exit_status: walk_exit_status(&summary),
+    })
+}
+
+/// The directory whose listed entries are being turned into usage rows.
+struct UsageParent<'a> {
+    path: &'a Path,
+    size: u64,
+    /// Prefix drawn before the branch of every row in this directory.
+    prefix: &'a str,
+}
+
+/// Append rows for a directory's entries and, depth first, their children.
+///
+/// Entries under [`MIN_USAGE_SHARE`] of the parent are folded into one
+/// "N other entries" row placed last, unless only one entry would be folded.
+fn append_usage_rows(
+    rows: &mut Vec<UsageRow>,
+    listings: &mut HashMap<PathBuf, Vec<WalkEntry>>,
+    parent: &UsageParent<'_>,
+    params: &Params,
+    gitignore_cache: &mut GitignoreCache,
+    usage: &UsageCache,
+) {
+    let Some(entries) = listings.remove(parent.path) else {
+        return;
+    };
+    let entries = entries
+        .into_iter()
+        .map(|entry| {
+            let info =
+                walk_entry_file_info(&entry, params, gitignore_cache, usage);
+            let share = usage_share(info.size, parent.size);
+            (entry, info, share)
+        })
+        .collect::<Vec<_>>();
+    let small = entries
+        .iter()
+        .filter(|(_, _, share)| *share < MIN_USAGE_SHARE)
+        .count();
+    let (shown, folded): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .partition(|(_, _, share)| small < 2 || *share >= MIN_USAGE_SHARE);
+    let row_count = shown.len() + usize::from(!folded.is_empty());
+    let style = params.tree_style;
+
+    for (index, (entry, info, share)) in shown.into_iter().enumerate() {
+        let is_last = index + 1 == row_count;
+        let size = info.size;
+        rows.push(UsageRow {
+            name: UsageName::Entry(Box::new(info)),
+            name_prefix: format!("{}{}", parent.prefix, style.branch(is_last)),
+            size,
+            share,
+        });
+
+        if entry.descend {
+            let prefix =
+                format!("{}{}", parent.prefix, style.continuation(is_last));
+            append_usage_rows(
+                rows,
+                listings,
+                &UsageParent {
+                    path: &entry.path,
+                    size,
+                    prefix: &prefix,
+                },
+                params,
+                gitignore_cache,
+                usage,
+            );
+        }
+    }
+
+    if !folded.is_empty() {
+        let size = folded.iter().map(|(_, info, _)| info.size).sum();
+        rows.push(UsageRow {
+            name: UsageName::Others(folded.len()),
+            name_prefix: format!("{}{}", parent.prefix, style.branch(true)),
+            size,
+            share: usage_share(size, parent.size),
+        });
+    }
+}
+
+fn usage_share(size: u64, parent_size: u64) -> f64 {
+    if parent_size == 0 {
+        0.0
+    } else {
+        size as f64 / parent_size as f64
+    }
+}
+
+/// Build display metadata for an entry reported by the walker.
+fn walk_entry_file_info(
+    entry: &WalkEntry,
+    params: &Params,
+    gitignore_cache: &mut GitignoreCache,
+    usage: &UsageCache,
+) -> FileInfo {
+    let mut info = create_file_info_from_metadata_with_gitignore(
+        &entry.path,
+        &entry.metadata,
+        &listed_entry_params(params, &entry.name),
+        gitignore_cache,
+        usage,
+    );
+    preserve_synthetic_dot_name(&mut info, &entry.name);
+    if entry.mount_point {
+        mark_mount_point(&mut info);
+    }
+    if !entry.chain.is_empty() {
+        let chain = entry
+            .chain
+            .iter()
+            .map(|name| format!("{}/", quote_name(name, params)))
+            .collect::<String>();
+        info.display_name.insert_str(0, &chain);
+        info.short_name.insert_str(0, &chain);
+    }
+    info
 }
 
 /// Return whether an explicit operand should be listed as a directory.
@@ -793,37 +1650,41 @@ fn is_display_directory(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-/// Return whether traversal may descend into a discovered child directory.
-///
-/// Unlike [`is_display_directory`], this intentionally does not follow
-/// symlinks. Recursive and tree traversal must not chase symlinked directories
-/// because that can loop back into an ancestor.
-fn is_recursable_directory(path: &Path) -> bool {
-    fs::symlink_metadata(path)
-        .map(|metadata| platform::classify_entry(path, &metadata).may_recurse)
-        .unwrap_or(false)
-}
-
-fn should_prune_directory(path: &Path, params: &Params) -> bool {
-    path.file_name()
-        .and_then(|name| name.to_str())
-        .is_some_and(|name| {
-            params.prune_dirs.iter().any(|pruned| pruned == name)
-        })
-}
-
 fn recursive_section_header(
     path: &Path,
     visible_entry_depth: usize,
+    params: &Params,
 ) -> Option<String> {
     let header_path = path.strip_prefix(".").unwrap_or(path);
     if visible_entry_depth == 1 && header_path.as_os_str().is_empty() {
-        None
+        // Dired records every section, the root included, like `ls -R`.
+        params.dired.then(|| quote_path(path, params))
     } else {
-        Some(display_path(header_path))
+        Some(quote_path(header_path, params))
     }
 }
 
 fn report_path_error(path: &Path, err: &io::Error) {
     eprintln!("{}", format_path_error(path, err));
 }
+
+/// Report a traversal failure and count it in the walk summary.
+fn report_walk_error(error: &WalkError, summary: &mut WalkSummary) {
+    report_path_error(&error.path, &error.error);
+    summary.errors += 1;
+    summary.cycles += usize::from(error.cycle);
+}
+
+/// Return the exit status owed to the errors counted in `summary`.
+///
+/// Like GNU `ls`, an unreadable entry or subdirectory is a minor problem and
+/// a directory cycle is a serious one.
+fn walk_exit_status(summary: &WalkSummary) -> i32 {
+    if summary.cycles > 0 {
+        EXIT_SERIOUS_TROUBLE
+    } else if summary.errors > 0 {
+        EXIT_MINOR_TROUBLE
+    } else {
+        0
+    }
+}
@@ -5,15 +5,16 @@
 //! the rest of the application can work with one normalized representation.
 
 use clap::ArgGroup;
-use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command, ValueEnum};
 use std::env;
 use std::ffi::OsString;
 
 use crate::{
     IndicatorStyle,
     structs::{
-        AttributeDisplay, IconDisplay, PermissionDisplay, ShortFormat,
-        SortMode,
+        AttributeDisplay, ColorDisplay, HyperlinkDisplay, IconDisplay,
+        PermissionDisplay, QuotingStyle, ShortFormat, SortMode, TotalSize,
+        TreeStyle,
     },
 };
 
@@ -22,15 +23,40 @@ const ARG_ALMOST_ALL: &str = "almost_all";
 const ARG_LONG: &str = "long";
 const ARG_VERTICAL: &str = "vertical";
 const ARG_ACROSS: &str = "across";
+const ARG_COMMAS: &str = "commas";
+const ARG_SINGLE_COLUMN: &str = "single_column";
 const ARG_FORMAT: &str = "format";
+const ARG_WIDTH: &str = "width";
+const ARG_TABSIZE: &str = "tabsize";
 const ARG_HEADER: &str = "header";
+const ARG_NUMERIC_UID_GID: &str = "numeric_uid_gid";
+const ARG_LONG_NO_OWNER: &str = "long_no_owner";
+const ARG_LONG_NO_GROUP: &str = "long_no_group";
+const ARG_NO_GROUP: &str = "no_group";
 const ARG_HUMAN_READABLE: &str = "human_readable";
 const ARG_SI: &str = "si";
+const ARG_TOTAL_SIZE: &str = "total_size";
+const ARG_DIRECTORY: &str = "directory";
+const ARG_DIRED: &str = "dired";
+const ARG_ZERO: &str = "zero";
 const ARG_RECURSIVE: &str = "recursive";
 const ARG_TREE: &str = "tree";
 const ARG_TREE_LEVEL: &str = "tree_level";
+const ARG_TREE_STYLE: &str = "tree_style";
+const ARG_SUMMARY: &str = "summary";
+const ARG_USAGE: &str = "usage";
+const ARG_COLLAPSE: &str = "collapse";
+const ARG_PRUNE_EMPTY: &str = "prune_empty";
+const ARG_DIRS_ONLY: &str = "dirs_only";
+const ARG_FROM_STDIN: &str = "from_stdin";
+const ARG_MAX_ENTRIES: &str = "max_entries";
+const ARG_NULL_SEPARATED: &str = "null_separated";
 const ARG_PRUNE_NOISY_DIRS: &str = "prune_noisy_dirs";
 const ARG_PRUNE_DIR: &str = "prune_dir";
+const ARG_DEREFERENCE: &str = "dereference";
+const ARG_DEREFERENCE_COMMAND_LINE: &str = "dereference_command_line";
+const ARG_ONE_FILE_SYSTEM: &str = "one_file_system";
+const ARG_THREADS: &str = "threads";
 const ARG_PATHS: &str = "paths";
 const ARG_SLASH: &str = "slash";
 const ARG_INDICATOR_STYLE: &str = "indicator_style";
@@ -49,18 +75,59 @@ const ARG_REVERSE: &str = "reverse";
 const ARG_ICONS: &str = "icons";
 const ARG_NO_ICONS: &str = "no_icons";
 const ARG_NO_COLOR: &str = "no_color";
+const ARG_COLOR: &str = "color";
+const ARG_HYPERLINK: &str = "hyperlink";
+const ARG_QUOTING_STYLE: &str = "quoting_style";
+const ARG_QUOTE_NAME: &str = "quote_name";
+const ARG_ESCAPE: &str = "escape";
+const ARG_LITERAL: &str = "literal";
+const ARG_HIDE_CONTROL_CHARS: &str = "hide_control_chars";
+const ARG_SHOW_CONTROL_CHARS: &str = "show_control_chars";
 const ARG_NO_PERMISSION_COLORS: &str = "no_permission_colors";
 const ARG_PERMISSIONS: &str = "permissions";
 const ARG_ATTRIBUTES: &str = "attributes";
 const ARG_NO_TIME_GRADIENT: &str = "no_time_gradient";
 const ARG_NO_SIZE_COLORS: &str = "no_size_colors";
 const ARG_GITIGNORE: &str = "gitignore";
+const ARG_NO_LSPIGNORE: &str = "no_lspignore";
 const ARG_VERSION: &str = "version";
 const ARG_FUZZY_TIME: &str = "fuzzy_time";
 const ARG_HELP: &str = "help";
 const ARG_INDICATOR_GROUP: &str = "indicator_style_group";
 const ARG_TREE_MODE_GROUP: &str = "tree_mode_group";
 
+/// Output formats accepted by `--format`, with GNU's word aliases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+enum FormatWord {
+    Vertical,
+    #[value(alias = "horizontal")]
+    Across,
+    Commas,
+    SingleColumn,
+    #[value(alias = "verbose")]
+    Long,
+}
+
+/// Listing layout picked by one format selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FormatSelection {
+    Long,
+    Short(ShortFormat),
+}
+
+impl From<FormatWord> for FormatSelection {
+    fn from(word: FormatWord) -> Self {
+        match word {
+            FormatWord::Vertical => Self::Short(ShortFormat::Vertical),
+            FormatWord::Across => Self::Short(ShortFormat::Across),
+            FormatWord::Commas => Self::Short(ShortFormat::Commas),
+            FormatWord::SingleColumn => Self::Short(ShortFormat::SingleColumn),
+            FormatWord::Long => Self::Long,
+        }
+    }
+}
+
 /// CLI compatibility mode used when building the clap command.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum CompatMode {
@@ -97,22 +164,66 @@ pub struct Flags {
     pub long: bool,
     /// Force a short-format layout.
     pub short_format: Option<ShortFormat>,
+    /// Line width for short-format layouts, where 0 means unlimited.
+    pub width: Option<usize>,
+    /// Tab stop spacing used to pad short-format columns.
+    pub tabsize: Option<usize>,
     /// Show a title row in long-format output.
     pub header: bool,
+    /// Show numeric user and group IDs in long-format output.
+    pub numeric_uid_gid: bool,
+    /// Omit the owner column from long-format output.
+    pub no_owner: bool,
+    /// Omit the group column from long-format output.
+    pub no_group: bool,
     /// Render human-readable file sizes in long format.
     pub human_readable: bool,
     /// Render human-readable file sizes using powers of 1000.
     pub si: bool,
+    /// Show recursive directory totals in the size column.
+    pub total_size: Option<TotalSize>,
+    /// List directory operands themselves instead of their contents.
+    pub directory: bool,
+    /// Emit Emacs dired offsets after long-format output.
+    pub dired: bool,
+    /// End each output line with NUL instead of a newline.
+    pub zero: bool,
     /// Recurse into child directories.
     pub recursive: bool,
     /// Render long-format tree output.
     pub tree: bool,
     /// Maximum visible entry depth for recursive/tree output.
     pub tree_level: Option<usize>,
+    /// Override the configured tree branch style.
+    pub tree_style: Option<TreeStyle>,
+    /// Print entry totals after recursive and tree output.
+    pub summary: bool,
+    /// Render a disk-usage tree with shares of each parent directory.
+    pub usage: bool,
+    /// Merge single-child directory chains into one tree row.
+    pub collapse: bool,
+    /// Hide tree directories left without visible descendants.
+    pub prune_empty: bool,
+    /// Show only directories in trees, each with its child counts.
+    pub dirs_only: bool,
+    /// Most entries to list per directory in tree and recursive output.
+    pub max_entries: Option<usize>,
+    /// Build the tree from paths read on stdin instead of the filesystem.
+    pub from_stdin: bool,
+    /// Split `--from-stdin` input on NUL bytes instead of newlines.
+    pub null_separated: bool,
     /// Enable the built-in noisy-directory traversal prune preset.
     pub prune_noisy_dirs: bool,
     /// Directory basenames to skip while traversing recursive/tree output.
     pub prune_dirs: Vec<String>,
+    /// Follow every symlink while listing and traversing.
+    pub dereference: bool,
+    /// Follow symlinks named on the command line.
+    pub dereference_command_line: bool,
+    /// Do not descend into directories on other filesystems.
+    pub one_file_system: bool,
+    /// Override the configured traversal thread count; `0` picks one.
+    pub threads: Option<usize>,
     /// Raw path arguments collected from the CLI.
     pub paths: Vec<String>,
     /// Override the configured indicator style for this invocation.
@@ -127,8 +238,16 @@ pub struct Flags {
     pub icons: Option<IconDisplay>,
     /// Disable file and directory icons.
     pub no_icons: bool,
+    /// Override when output is colored.
+    pub color: Option<ColorDisplay>,
     /// Disable colored or styled output.
     pub no_color: bool,
+    /// Override when names link to their files.
+    pub hyperlink: Option<HyperlinkDisplay>,
+    /// Override how names, link targets, and headers are quoted.
+    pub quoting_style: Option<QuotingStyle>,
+    /// Force control characters to print as `?` or as they are.
+    pub hide_control_chars: Option<bool>,
     /// Disable permission and file-type colors in long-format output.
     pub no_permission_colors: bool,
     /// Override long-format permission display mode.
@@ -141,6 +260,8 @@ pub struct Flags {
     pub no_size_colors: bool,
     /// Dim paths matched by `.gitignore` rules.
     pub gitignore: bool,
+    /// Disable `.lspignore` hiding and traversal pruning.
+    pub no_lspignore: bool,
     /// Print version information and exit.
     pub version: bool,
     /// Render humanized relative timestamps.
@@ -208,15 +329,40 @@ fn build_command(mode: CompatMode) -> Command {
         .arg(long_arg())
         .arg(vertical_arg())
         .arg(across_arg())
+        .arg(commas_arg())
+        .arg(single_column_arg())
         .arg(format_arg())
+        .arg(width_arg())
+        .arg(tabsize_arg())
         .arg(header_arg())
+        .arg(numeric_uid_gid_arg())
+        .arg(long_no_owner_arg())
+        .arg(long_no_group_arg())
+        .arg(no_group_arg())
         .arg(human_readable_arg())
         .arg(si_arg())
+        .arg(total_size_arg())
+        .arg(directory_arg())
+        .arg(dired_arg(mode))
+        .arg(zero_arg())
         .arg(recursive_arg())
         .arg(tree_arg())
         .arg(tree_level_arg())
+        .arg(tree_style_arg())
+        .arg(summary_arg())
+        .arg(usage_arg())
+        .arg(collapse_arg())
+        .arg(prune_empty_arg())
+        .arg(dirs_only_arg())
+        .arg(max_entries_arg())
+        .arg(from_stdin_arg())
+        .arg(null_separated_arg())
         .arg(prune_noisy_dirs_arg())
         .arg(prune_dir_arg())
+        .arg(dereference_arg())
+        .arg(dereference_command_line_arg())
+        .arg(one_file_system_arg())
+        .arg(threads_arg())
         .arg(paths_arg())
         .arg(slash_arg(mode))
         .arg(file_type_arg(mode))
@@ -232,13 +378,22 @@ fn build_command(mode: CompatMode) -> Command {
         .arg(reverse_arg())
         .arg(icons_arg())
         .arg(no_icons_arg())
+        .arg(color_arg())
         .arg(no_color_arg(mode))
+        .arg(hyperlink_arg())
+        .arg(quoting_style_arg())
+        .arg(quote_name_arg())
+        .arg(escape_arg())
+        .arg(literal_arg(mode))
+        .arg(hide_control_chars_arg())
+        .arg(show_control_chars_arg())
         .arg(no_permission_colors_arg())
         .arg(permissions_arg())
         .arg(attributes_arg())
         .arg(no_time_gradient_arg())
         .arg(no_size_colors_arg())
         .arg(gitignore_arg(mode))
+        .arg(no_lspignore_arg())
         .arg(version_arg())
         .arg(fuzzy_time_arg(mode))
         .arg(help_arg())
@@ -267,9 +422,11 @@ fn indicator_group(mode: CompatMode) -> ArgGroup {
 }
 
 fn tree_mode_group() -> ArgGroup {
-    ArgGroup::new(ARG_TREE_MODE_GROUP)
-        .multiple(false)
-        .args([ARG_RECURSIVE, ARG_TREE])
+    ArgGroup::new(ARG_TREE_MODE_GROUP).multiple(false).args([
+        ARG_RECURSIVE,
+        ARG_TREE,
+        ARG_USAGE,
+    ])
 }
 
 fn help_arg() -> Arg {
@@ -317,12 +474,47 @@ fn across_arg() -> Arg {
         .help("List entries in rows from left to right")
 }
 
+fn commas_arg() -> Arg {
+    Arg::new(ARG_COMMAS)
+        .short('m')
+        .action(ArgAction::SetTrue)
+        .help("List entries separated by commas")
+}
+
+fn single_column_arg() -> Arg {
+    Arg::new(ARG_SINGLE_COLUMN)
+        .short('1')
+        .action(ArgAction::SetTrue)
+        .help("List one entry per line")
+}
+
 fn format_arg() -> Arg {
     Arg::new(ARG_FORMAT)
         .long("format")
         .value_name("FORMAT")
-        .value_parser(clap::value_parser!(ShortFormat))
-        .help("Select short output format: vertical or across")
+        .value_parser(clap::value_parser!(FormatWord))
+        .help(
+            "Select output format: vertical, across, commas, single-column, \
+             or long",
+        )
+}
+
+fn width_arg() -> Arg {
+    Arg::new(ARG_WIDTH)
+        .short('w')
+        .long("width")
+        .value_name("COLS")
+        .value_parser(clap::value_parser!(usize))
+        .help("Fit short output in COLS columns (0 = unlimited)")
+}
+
+fn tabsize_arg() -> Arg {
+    Arg::new(ARG_TABSIZE)
+        .short('T')
+        .long("tabsize")
+        .value_name("COLS")
+        .value_parser(clap::value_parser!(usize))
+        .help("Pad short output columns with tabs every COLS columns")
 }
 
 fn header_arg() -> Arg {
@@ -332,6 +524,36 @@ fn header_arg() -> Arg {
         .help("Show a title row in long-format output")
 }
 
+fn numeric_uid_gid_arg() -> Arg {
+    Arg::new(ARG_NUMERIC_UID_GID)
+        .short('n')
+        .long("numeric-uid-gid")
+        .action(ArgAction::SetTrue)
+        .help("Like -l, but list numeric user and group IDs")
+}
+
+fn long_no_owner_arg() -> Arg {
+    Arg::new(ARG_LONG_NO_OWNER)
+        .short('g')
+        .action(ArgAction::SetTrue)
+        .help("Like -l, but do not list the owner")
+}
+
+fn long_no_group_arg() -> Arg {
+    Arg::new(ARG_LONG_NO_GROUP)
+        .short('o')
+        .action(ArgAction::SetTrue)
+        .help("Like -l, but do not list the group")
+}
+
+fn no_group_arg() -> Arg {
+    Arg::new(ARG_NO_GROUP)
+        .short('G')
+        .long("no-group")
+        .action(ArgAction::SetTrue)
+        .help("In long-format output, do not list the group")
+}
+
 fn human_readable_arg() -> Arg {
     Arg::new(ARG_HUMAN_READABLE)
         .short('h')
@@ -347,6 +569,47 @@ fn si_arg() -> Arg {
         .help("with -l, print sizes using 1000-byte units, like 1k 234M 2G")
 }
 
+fn total_size_arg() -> Arg {
+    Arg::new(ARG_TOTAL_SIZE)
+        .long("total-size")
+        .value_name("KIND")
+        .num_args(0..=1)
+        .require_equals(true)
+        .default_missing_value("apparent")
+        .value_parser(clap::value_parser!(TotalSize))
+        .help("Show recursive directory sizes: apparent (default) or disk")
+}
+
+fn directory_arg() -> Arg {
+    Arg::new(ARG_DIRECTORY)
+        .short('d')
+        .long("directory")
+        .action(ArgAction::SetTrue)
+        .conflicts_with(ARG_FROM_STDIN)
+        .help("List directories themselves, not their contents")
+}
+
+fn dired_arg(mode: CompatMode) -> Arg {
+    let arg = Arg::new(ARG_DIRED)
+        .long("dired")
+        .action(ArgAction::SetTrue)
+        .conflicts_with(ARG_FROM_STDIN)
+        .help("Use long format and end with byte offsets for Emacs dired");
+
+    match mode {
+        CompatMode::Native => arg,
+        CompatMode::Gnu => arg.short('D'),
+    }
+}
+
+fn zero_arg() -> Arg {
+    Arg::new(ARG_ZERO)
+        .long("zero")
+        .action(ArgAction::SetTrue)
+        .conflicts_with(ARG_DIRED)
+        .help("End each output line with NUL, not newline")
+}
+
 fn recursive_arg() -> Arg {
     Arg::new(ARG_RECURSIVE)
         .short('R')
@@ -366,11 +629,83 @@ fn tree_level_arg() -> Arg {
     Arg::new(ARG_TREE_LEVEL)
         .long("level")
         .value_name("N")
-        .value_parser(parse_tree_level)
+        .value_parser(parse_positive_count)
         .requires(ARG_TREE_MODE_GROUP)
         .help("Limit recursive or tree output to N visible entry levels")
 }
 
+fn tree_style_arg() -> Arg {
+    Arg::new(ARG_TREE_STYLE)
+        .long("tree-style")
+        .value_name("STYLE")
+        .value_parser(clap::value_parser!(TreeStyle))
+        .help("Draw tree branches as compact, classic, ascii, or indent")
+}
+
+fn summary_arg() -> Arg {
+    Arg::new(ARG_SUMMARY)
+        .long("summary")
+        .action(ArgAction::SetTrue)
+        .help("Print directory, file, and size totals after recursive output")
+}
+
+fn usage_arg() -> Arg {
+    Arg::new(ARG_USAGE)
+        .long("usage")
+        .action(ArgAction::SetTrue)
+        .help("Display a disk usage tree with percentages and bars")
+}
+
+fn collapse_arg() -> Arg {
+    Arg::new(ARG_COLLAPSE)
+        .long("collapse")
+        .action(ArgAction::SetTrue)
+        .help("Show single-child directory chains on one tree row")
+}
+
+fn prune_empty_arg() -> Arg {
+    Arg::new(ARG_PRUNE_EMPTY)
+        .long("prune-empty")
+        .action(ArgAction::SetTrue)
+        .help("Omit tree directories with no visible descendants")
+}
+
+fn dirs_only_arg() -> Arg {
+    Arg::new(ARG_DIRS_ONLY)
+        .long("dirs-only")
+        .action(ArgAction::SetTrue)
+        .conflicts_with(ARG_FROM_STDIN)
+        .help("Show only directories in trees, with file and subdirectory counts")
+}
+
+fn max_entries_arg() -> Arg {
+    Arg::new(ARG_MAX_ENTRIES)
+        .long("max-entries")
+        .value_name("N")
+        .value_parser(parse_positive_count)
+        .help(
+            "List at most N entries per directory in tree or recursive output",
+        )
+}
+
+fn from_stdin_arg() -> Arg {
+    Arg::new(ARG_FROM_STDIN)
+        .long("from-stdin")
+        .action(ArgAction::SetTrue)
+        .requires(ARG_TREE)
+        .conflicts_with(ARG_PATHS)
+        .help("Build the tree from a list of paths read on stdin")
+}
+
+fn null_separated_arg() -> Arg {
+    Arg::new(ARG_NULL_SEPARATED)
+        .short('0')
+        .long("null")
+        .action(ArgAction::SetTrue)
+        .requires(ARG_FROM_STDIN)
+        .help("Read NUL-separated paths with --from-stdin")
+}
+
 fn prune_noisy_dirs_arg() -> Arg {
     Arg::new(ARG_PRUNE_NOISY_DIRS)
         .long("prune-noisy-dirs")
@@ -386,7 +721,38 @@ fn prune_dir_arg() -> Arg {
         .help("Skip descending into directory basename NAME")
 }
 
-fn parse_tree_level(value: &str) -> Result<usize, String> {
+fn dereference_arg() -> Arg {
+    Arg::new(ARG_DEREFERENCE)
+        .short('L')
+        .long("dereference")
+        .action(ArgAction::SetTrue)
+        .help("Show and traverse the targets of symbolic links")
+}
+
+fn dereference_command_line_arg() -> Arg {
+    Arg::new(ARG_DEREFERENCE_COMMAND_LINE)
+        .short('H')
+        .long("dereference-command-line")
+        .action(ArgAction::SetTrue)
+        .help("Follow symbolic links listed on the command line")
+}
+
+fn one_file_system_arg() -> Arg {
+    Arg::new(ARG_ONE_FILE_SYSTEM)
+        .long("one-file-system")
+        .action(ArgAction::SetTrue)
+        .help("Do not descend into directories on other filesystems")
+}
+
+fn threads_arg() -> Arg {
+    Arg::new(ARG_THREADS)
+        .long("threads")
+        .value_name("N")
+        .value_parser(clap::value_parser!(usize))
+        .help("Read directories with N threads when traversing (0 = auto)")
+}
+
+fn parse_positive_count(value: &str) -> Result<usize, String> {
     match value.parse::<usize>() {
         Ok(0) => Err(String::from("value must be at least 1")),
         Ok(level) => Ok(level),
@@ -544,20 +910,102 @@ fn icons_arg() -> Arg {
         .help("Display icons: auto, always, or never")
 }
 
+fn hyperlink_arg() -> Arg {
+    Arg::new(ARG_HYPERLINK)
+        .long("hyperlink")
+        .num_args(0..=1)
+        .require_equals(true)
+        .value_name("WHEN")
+        .default_missing_value("always")
+        .value_parser(clap::value_parser!(HyperlinkDisplay))
+        .help("Link names to their files: auto, always (default), or never")
+}
+
+fn color_arg() -> Arg {
+    Arg::new(ARG_COLOR)
+        .long("color")
+        .num_args(0..=1)
+        .require_equals(true)
+        .value_name("WHEN")
+        .default_missing_value("always")
+        .value_parser(clap::value_parser!(ColorDisplay))
+        .overrides_with(ARG_NO_COLOR)
+        .help("Color output: auto, always (default), or never")
+}
+
 fn no_color_arg(mode: CompatMode) -> Arg {
     match mode {
         CompatMode::Native => Arg::new(ARG_NO_COLOR)
             .short('N')
             .long("no-color")
             .action(ArgAction::SetTrue)
+            .overrides_with(ARG_COLOR)
             .help("Do not display colored or styled output"),
         CompatMode::Gnu => Arg::new(ARG_NO_COLOR)
             .long("no-color")
             .action(ArgAction::SetTrue)
+            .overrides_with(ARG_COLOR)
             .help("Do not display colored or styled output"),
     }
 }
 
+fn quoting_style_arg() -> Arg {
+    Arg::new(ARG_QUOTING_STYLE)
+        .long("quoting-style")
+        .value_name("WORD")
+        .value_parser(clap::value_parser!(QuotingStyle))
+        .overrides_with_all([ARG_QUOTE_NAME, ARG_ESCAPE, ARG_LITERAL])
+        .help("Quote entry names using the given style")
+}
+
+fn quote_name_arg() -> Arg {
+    Arg::new(ARG_QUOTE_NAME)
+        .short('Q')
+        .long("quote-name")
+        .action(ArgAction::SetTrue)
+        .overrides_with_all([ARG_QUOTING_STYLE, ARG_ESCAPE, ARG_LITERAL])
+        .help("Enclose entry names in double quotes")
+}
+
+fn escape_arg() -> Arg {
+    Arg::new(ARG_ESCAPE)
+        .short('b')
+        .long("escape")
+        .action(ArgAction::SetTrue)
+        .overrides_with_all([ARG_QUOTING_STYLE, ARG_QUOTE_NAME, ARG_LITERAL])
+        .help("Print C-style escapes for nongraphic characters")
+}
+
+fn literal_arg(mode: CompatMode) -> Arg {
+    let arg = Arg::new(ARG_LITERAL)
+        .long("literal")
+        .action(ArgAction::SetTrue)
+        .overrides_with_all([ARG_QUOTING_STYLE, ARG_QUOTE_NAME, ARG_ESCAPE])
+        .help("Print entry names without quoting");
+
+    match mode {
+        CompatMode::Native => arg,
+        CompatMode::Gnu => arg.short('N'),
+    }
+}
+
+fn hide_control_chars_arg() -> Arg {
+    Arg::new(ARG_HIDE_CONTROL_CHARS)
+        .short('q')
+        .long("hide-control-chars")
+        .action(ArgAction::SetTrue)
+        .overrides_with(ARG_SHOW_CONTROL_CHARS)
+        .help("Print ? instead of nongraphic characters")
+}
+
+fn show_control_chars_arg() -> Arg {
+    Arg::new(ARG_SHOW_CONTROL_CHARS)
+        .long("show-control-chars")
+        .action(ArgAction::SetTrue)
+        .overrides_with(ARG_HIDE_CONTROL_CHARS)
+        .help("Print nongraphic characters as they are")
+}
+
 fn no_permission_colors_arg() -> Arg {
     Arg::new(ARG_NO_PERMISSION_COLORS)
         .long("no-permission-colors")
@@ -613,6 +1061,13 @@ fn gitignore_arg(mode: CompatMode) -> Arg {
     }
 }
 
+fn no_lspignore_arg() -> Arg {
+    Arg::new(ARG_NO_LSPIGNORE)
+        .long("no-lspignore")
+        .action(ArgAction::SetTrue)
+        .help("Do not hide or prune entries matched by .lspignore files")
+}
+
 fn version_arg() -> Arg {
     Arg::new(ARG_VERSION)
         .short('V')
@@ -637,33 +1092,49 @@ fn fuzzy_time_arg(mode: CompatMode) -> Arg {
 }
 
 fn flags_from_matches(mode: CompatMode, matches: &ArgMatches) -> Flags {
+    let (long, short_format) = format_from_matches(mode, matches);
+
     Flags {
         show_all: matches.get_flag(ARG_SHOW_ALL)
             || sort_flag_is_present(matches, ARG_NO_SORT_ALL),
         almost_all: matches.get_flag(ARG_ALMOST_ALL),
-        long: long_format_from_matches(mode, matches),
-        short_format: matches
-            .get_one::<ShortFormat>(ARG_FORMAT)
-            .copied()
-            .or_else(|| {
-                matches
-                    .get_flag(ARG_VERTICAL)
-                    .then_some(ShortFormat::Vertical)
-            })
-            .or_else(|| {
-                matches.get_flag(ARG_ACROSS).then_some(ShortFormat::Across)
-            }),
+        long,
+        short_format,
+        width: matches.get_one::<usize>(ARG_WIDTH).copied(),
+        tabsize: matches.get_one::<usize>(ARG_TABSIZE).copied(),
         header: matches.get_flag(ARG_HEADER),
+        numeric_uid_gid: matches.get_flag(ARG_NUMERIC_UID_GID),
+        no_owner: matches.get_flag(ARG_LONG_NO_OWNER),
+        no_group: matches.get_flag(ARG_LONG_NO_GROUP)
+            || matches.get_flag(ARG_NO_GROUP),
         human_readable: matches.get_flag(ARG_HUMAN_READABLE),
         si: matches.get_flag(ARG_SI),
+        total_size: matches.get_one::<TotalSize>(ARG_TOTAL_SIZE).copied(),
+        directory: matches.get_flag(ARG_DIRECTORY),
+        dired: matches.get_flag(ARG_DIRED),
+        zero: matches.get_flag(ARG_ZERO),
         recursive: matches.get_flag(ARG_RECURSIVE),
         tree: matches.get_flag(ARG_TREE),
         tree_level: matches.get_one::<usize>(ARG_TREE_LEVEL).copied(),
+        tree_style: matches.get_one::<TreeStyle>(ARG_TREE_STYLE).copied(),
+        summary: matches.get_flag(ARG_SUMMARY),
+        usage: matches.get_flag(ARG_USAGE),
+        collapse: matches.get_flag(ARG_COLLAPSE),
+        prune_empty: matches.get_flag(ARG_PRUNE_EMPTY),
+        dirs_only: matches.get_flag(ARG_DIRS_ONLY),
+        max_entries: matches.get_one::<usize>(ARG_MAX_ENTRIES).copied(),
+        from_stdin: matches.get_flag(ARG_FROM_STDIN),
+        null_separated: matches.get_flag(ARG_NULL_SEPARATED),
         prune_noisy_dirs: matches.get_flag(ARG_PRUNE_NOISY_DIRS),
         prune_dirs: matches
             .get_many::<String>(ARG_PRUNE_DIR)
             .map(|values| values.cloned().collect())
             .unwrap_or_default(),
+        dereference: matches.get_flag(ARG_DEREFERENCE),
+        dereference_command_line: matches
+            .get_flag(ARG_DEREFERENCE_COMMAND_LINE),
+        one_file_system: matches.get_flag(ARG_ONE_FILE_SYSTEM),
+        threads: matches.get_one::<usize>(ARG_THREADS).copied(),
         paths: matches
             .get_many::<String>(ARG_PATHS)
             .map(|values| values.cloned().collect())
@@ -674,7 +1145,28 @@ fn flags_from_matches(mode: CompatMode, matches: &ArgMatches) -> Flags {
         reverse: matches.get_flag(ARG_REVERSE),
         icons: matches.get_one::<IconDisplay>(ARG_ICONS).copied(),
         no_icons: matches.get_flag(ARG_NO_ICONS),
+        color: matches.get_one::<ColorDisplay>(ARG_COLOR).copied(),
         no_color: matches.get_flag(ARG_NO_COLOR),
+        hyperlink: matches.get_one::<HyperlinkDisplay>(ARG_HYPERLINK).copied(),
+        quoting_style: matches
+            .get_one::<QuotingStyle>(ARG_QUOTING_STYLE)
+            .copied()
+            .or_else(|| {
+                matches.get_flag(ARG_QUOTE_NAME).then_some(QuotingStyle::C)
+            })
+            .or_else(|| {
+                matches.get_flag(ARG_ESCAPE).then_some(QuotingStyle::Escape)
+            })
+            .or_else(|| {
+                matches
+                    .get_flag(ARG_LITERAL)
+                    .then_some(QuotingStyle::Literal)
+            }),
+        hide_control_chars: if matches.get_flag(ARG_HIDE_CONTROL_CHARS) {
+            Some(true)
+        } else {
+            matches.get_flag(ARG_SHOW_CONTROL_CHARS).then_some(false)
+        },
         no_permission_colors: matches.get_flag(ARG_NO_PERMISSION_COLORS),
         permissions: matches
             .get_one::<PermissionDisplay>(ARG_PERMISSIONS)
@@ -685,32 +1177,110 @@ fn flags_from_matches(mode: CompatMode, matches: &ArgMatches) -> Flags {
         no_time_gradient: matches.get_flag(ARG_NO_TIME_GRADIENT),
         no_size_colors: matches.get_flag(ARG_NO_SIZE_COLORS),
         gitignore: matches.get_flag(ARG_GITIGNORE),
+        no_lspignore: matches.get_flag(ARG_NO_LSPIGNORE),
         version: matches.get_flag(ARG_VERSION),
         fuzzy_time: matches.get_flag(ARG_FUZZY_TIME),
     }
 }
 
-fn long_format_from_matches(mode: CompatMode, matches: &ArgMatches) -> bool {
-    if !matches.get_flag(ARG_LONG) {
-        return false;
+/// Resolve long format and the short layout from every format selector.
+///
+/// `-n`, `-g`, and `-o` select long format like `-l`.
+///
+/// Native mode keeps a fixed precedence: any long selector wins, then
+/// `--format`, `-C`, `-x`, `-m`, and `-1`. GNU mode lets the last selector on
+/// the command line win, so `ls -l` aliases can still be switched with `-C`.
+fn format_from_matches(
+    mode: CompatMode,
+    matches: &ArgMatches,
+) -> (bool, Option<ShortFormat>) {
+    let mut candidates = Vec::new();
+
+    if let (Some(indices), Some(words)) = (
+        matches.indices_of(ARG_FORMAT),
+        matches.get_many::<FormatWord>(ARG_FORMAT),
+    ) {
+        candidates.extend(
+            indices.zip(words.map(|word| FormatSelection::from(*word))),
+        );
     }
 
-    if mode == CompatMode::Native
-        || !sort_flag_is_present(matches, ARG_NO_SORT_ALL)
-    {
-        return true;
+    for (arg, selection) in [
+        (ARG_LONG, FormatSelection::Long),
+        (ARG_NUMERIC_UID_GID, FormatSelection::Long),
+        (ARG_LONG_NO_OWNER, FormatSelection::Long),
+        (ARG_LONG_NO_GROUP, FormatSelection::Long),
+        (ARG_VERTICAL, FormatSelection::Short(ShortFormat::Vertical)),
+        (ARG_ACROSS, FormatSelection::Short(ShortFormat::Across)),
+        (ARG_COMMAS, FormatSelection::Short(ShortFormat::Commas)),
+        (
+            ARG_SINGLE_COLUMN,
+            FormatSelection::Short(ShortFormat::SingleColumn),
+        ),
+    ] {
+        if matches.get_flag(arg)
+            && let Some(index) =
+                matches.indices_of(arg).and_then(Iterator::last)
+        {
+            candidates.push((index, selection));
+        }
     }
 
-    let long_index = matches
-        .indices_of(ARG_LONG)
-        .and_then(Iterator::last)
-        .expect("present long-format flag should have an argument index");
-    let no_sort_index = matches
-        .indices_of(ARG_NO_SORT_ALL)
-        .and_then(Iterator::last)
-        .expect("present no-sort-all flag should have an argument index");
+    match mode {
+        CompatMode::Native => {
+            let long = candidates
+                .iter()
+                .any(|(_, selection)| *selection == FormatSelection::Long);
+            let short_format =
+                candidates
+                    .iter()
+                    .find_map(|(_, selection)| match selection {
+                        FormatSelection::Short(format) => Some(*format),
+                        FormatSelection::Long => None,
+                    });
+            (long, short_format)
+        }
+        CompatMode::Gnu => {
+            // As in GNU `ls`, `-1` has no effect after `-l`, although
+            // `--format=single-column` still switches away from it.
+            let single_column_flag = matches
+                .get_flag(ARG_SINGLE_COLUMN)
+                .then(|| {
+                    matches
+                        .indices_of(ARG_SINGLE_COLUMN)
+                        .and_then(Iterator::last)
+                })
+                .flatten();
+            candidates.sort_by_key(|(index, _)| *index);
+            let last = candidates.into_iter().fold(None, |last, candidate| {
+                match (last, candidate) {
+                    (
+                        Some((_, FormatSelection::Long)),
+                        (index, FormatSelection::Short(_)),
+                    ) if Some(index) == single_column_flag => last,
+                    _ => Some(candidate),
+                }
+            });
+            match last {
+                Some((index, FormatSelection::Long)) => {
+                    (!no_sort_all_follows(matches, index), None)
+                }
+                Some((_, FormatSelection::Short(format))) => {
+                    (false, Some(format))
+                }
+                None => (false, None),
+            }
+        }
+    }
+}
 
-    long_index > no_sort_index
+/// Return whether GNU `-f` appears after `index`, which turns off `-l`.
+fn no_sort_all_follows(matches: &ArgMatches, index: usize) -> bool {
+    sort_flag_is_present(matches, ARG_NO_SORT_ALL)
+        && matches
+            .indices_of(ARG_NO_SORT_ALL)
+            .and_then(Iterator::last)
+            .is_some_and(|no_sort_index| no_sort_index > index)
 }
 
 fn sort_flag_is_present(matches: &ArgMatches, arg: &str) -> bool {
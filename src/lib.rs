@@ -13,8 +13,8 @@ pub mod structs;
 pub mod utils;
 
 pub use structs::{
-    FileInfo, IconDisplay, IndicatorStyle, NameStyle, Params, ShortFormat,
-    SortMode,
+    ColorDisplay, FileInfo, HyperlinkDisplay, IconDisplay, IndicatorStyle,
+    NameStyle, Params, QuotingStyle, ShortFormat, SortMode, SyntheticEntry,
 };
 
 #[cfg(test)]
@@ -27,6 +27,12 @@ mod cli_tests;
 #[path = "../tests/crate/common.rs"]
 mod common_tests;
 #[cfg(test)]
+#[path = "../tests/crate/dired.rs"]
+mod dired_tests;
+#[cfg(test)]
+#[path = "../tests/crate/disk_usage.rs"]
+mod disk_usage_tests;
+#[cfg(test)]
 #[path = "../tests/crate/file.rs"]
 mod file_tests;
 #[cfg(all(test, unix))]
@@ -39,9 +45,15 @@ mod file_windows_tests;
 #[path = "../tests/crate/gitignore.rs"]
 mod gitignore_tests;
 #[cfg(test)]
+#[path = "../tests/crate/hyperlink.rs"]
+mod hyperlink_tests;
+#[cfg(test)]
 #[path = "../tests/crate/icons.rs"]
 mod icons_tests;
 #[cfg(test)]
+#[path = "../tests/crate/quoting.rs"]
+mod quoting_tests;
+#[cfg(test)]
 #[path = "../tests/crate/render.rs"]
 mod render_tests;
 #[cfg(all(test, unix))]
@@ -53,3 +65,6 @@ mod render_windows_tests;
 #[cfg(test)]
 #[path = "../tests/crate/settings.rs"]
 mod settings_tests;
+#[cfg(test)]
+#[path = "../tests/crate/walk.rs"]
+mod walk_tests;
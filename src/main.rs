@@ -19,10 +19,11 @@ fn main() {
         exit(0);
     }
 
-    if let Err(e) =
-        lsplus::app::run_with_flags_and_config(args, &startup.params)
-    {
-        eprintln!("Error: {}", e);
-        exit(1);
+    match lsplus::app::run_with_flags_and_config(args, &startup.params) {
+        Ok(status) => exit(status),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
     }
 }
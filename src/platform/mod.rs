@@ -15,6 +15,16 @@ pub(crate) struct EntryClassification {
     pub(crate) may_render_link_target: bool,
 }
 
+/// Stable identity of a filesystem object on its device.
+///
+/// Traversal compares identities to notice when a followed symlink leads back
+/// into a directory that is already being listed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct FileIdentity {
+    pub(crate) device: u64,
+    pub(crate) inode: u64,
+}
+
 /// File types that can appear in long-format output.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum LongFormatFileType {
@@ -98,6 +108,10 @@ pub(crate) struct LongFormatLayoutOptions {
     pub(crate) permission_display: PermissionDisplay,
     pub(crate) include_size_unit: bool,
     pub(crate) include_icon: bool,
+    #[cfg_attr(windows, allow(dead_code))]
+    pub(crate) include_owner: bool,
+    #[cfg_attr(windows, allow(dead_code))]
+    pub(crate) include_group: bool,
 }
 
 /// A platform-neutral long-format table column.
@@ -2,17 +2,17 @@
 
 use nix::unistd::{Group, User};
 use std::cmp::Ordering;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::io;
-use std::os::unix::ffi::OsStrExt;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
 use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use crate::platform::{
-    EntryClassification, FileDetails, LongColumn, LongFormatFileType,
-    LongFormatLayout, LongFormatLayoutOptions,
+    EntryClassification, FileDetails, FileIdentity, LongColumn,
+    LongFormatFileType, LongFormatLayout, LongFormatLayoutOptions,
 };
 use crate::structs::{AttributeDisplay, NameStyle, Params, PermissionDisplay};
 use crate::utils::format;
@@ -84,6 +84,37 @@ pub(crate) fn classify_entry(
     }
 }
 
+/// Return the device and inode of the object a path resolves to.
+pub(crate) fn file_identity(path: &Path) -> io::Result<FileIdentity> {
+    let metadata = fs::metadata(path)?;
+    Ok(FileIdentity {
+        device: metadata.dev(),
+        inode: metadata.ino(),
+    })
+}
+
+/// Return the identity shared by every hard link to a multiply-linked file.
+pub(crate) fn hardlink_identity(
+    _path: &Path,
+    metadata: &fs::Metadata,
+) -> Option<FileIdentity> {
+    (!metadata.is_dir() && metadata.nlink() > 1).then(|| FileIdentity {
+        device: metadata.dev(),
+        inode: metadata.ino(),
+    })
+}
+
+/// Return the bytes allocated on disk for an entry.
+pub(crate) fn allocated_size(_path: &Path, metadata: &fs::Metadata) -> u64 {
+    // `st_blocks` is always counted in 512-byte units.
+    metadata.blocks() * 512
+}
+
+/// Convert raw path bytes read from a pipe into a path.
+pub(crate) fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
 pub(crate) fn entry_name_is_hidden(name: &OsStr) -> bool {
     name.as_bytes().starts_with(b".")
 }
@@ -100,6 +131,7 @@ pub(crate) fn file_details(
     metadata: &fs::Metadata,
     classification: EntryClassification,
     _attribute_display: AttributeDisplay,
+    numeric_ids: bool,
 ) -> FileDetails {
     let file_type = classification.file_type.as_char().to_string();
 
@@ -110,8 +142,11 @@ pub(crate) fn file_details(
     let nlink = metadata.nlink();
     let size = metadata.size();
 
-    let user = get_username(metadata.uid());
-    let group = get_groupname(metadata.gid());
+    let (user, group) = if numeric_ids {
+        (metadata.uid().to_string(), metadata.gid().to_string())
+    } else {
+        (get_username(metadata.uid()), get_groupname(metadata.gid()))
+    };
 
     let mtime = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
 
@@ -201,6 +236,13 @@ pub(crate) fn normalize_path(path: PathBuf) -> PathBuf {
     path
 }
 
+/// Return this machine's host name for `file://` links, or an empty string.
+pub(crate) fn hostname() -> String {
+    nix::unistd::gethostname()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
 pub(crate) fn long_format_layout(
     options: &LongFormatLayoutOptions,
 ) -> LongFormatLayout {
@@ -220,12 +262,14 @@ pub(crate) fn long_format_layout(
         PermissionDisplay::None => {}
     }
 
-    columns.extend([
-        LongColumn::Links,
-        LongColumn::User,
-        LongColumn::Group,
-        LongColumn::Size,
-    ]);
+    columns.push(LongColumn::Links);
+    if options.include_owner {
+        columns.push(LongColumn::User);
+    }
+    if options.include_group {
+        columns.push(LongColumn::Group);
+    }
+    columns.push(LongColumn::Size);
     if options.include_size_unit {
         columns.push(LongColumn::Unit);
     }
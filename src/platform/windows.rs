@@ -6,7 +6,7 @@ use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::io;
 use std::os::windows::ffi::{OsStrExt, OsStringExt};
-use std::os::windows::fs::{FileTypeExt, MetadataExt};
+use std::os::windows::fs::{FileTypeExt, MetadataExt, OpenOptionsExt};
 use std::os::windows::io::AsRawHandle;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
@@ -15,20 +15,23 @@ use std::time::SystemTime;
 use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
 use windows_sys::Win32::Globalization::CompareStringOrdinal;
 use windows_sys::Win32::Storage::FileSystem::{
-    FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_COMPRESSED, FILE_ATTRIBUTE_DEVICE,
+    BY_HANDLE_FILE_INFORMATION, FILE_ATTRIBUTE_ARCHIVE,
+    FILE_ATTRIBUTE_COMPRESSED, FILE_ATTRIBUTE_DEVICE,
     FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_ENCRYPTED, FILE_ATTRIBUTE_HIDDEN,
     FILE_ATTRIBUTE_INTEGRITY_STREAM, FILE_ATTRIBUTE_NORMAL,
     FILE_ATTRIBUTE_NOT_CONTENT_INDEXED, FILE_ATTRIBUTE_OFFLINE,
     FILE_ATTRIBUTE_PINNED, FILE_ATTRIBUTE_READONLY,
     FILE_ATTRIBUTE_REPARSE_POINT, FILE_ATTRIBUTE_SPARSE_FILE,
     FILE_ATTRIBUTE_SYSTEM, FILE_ATTRIBUTE_TEMPORARY, FILE_ATTRIBUTE_UNPINNED,
-    FILE_ATTRIBUTE_VIRTUAL, FILE_TYPE_DISK, FindClose, FindFirstFileW,
-    GetFileType, WIN32_FIND_DATAW,
+    FILE_ATTRIBUTE_VIRTUAL, FILE_FLAG_BACKUP_SEMANTICS, FILE_TYPE_DISK,
+    FindClose, FindFirstFileW, GetCompressedFileSizeW,
+    GetFileInformationByHandle, GetFileType, INVALID_FILE_SIZE,
+    WIN32_FIND_DATAW,
 };
 
 use crate::platform::{
-    EntryClassification, FileDetails, LongColumn, LongFormatFileType,
-    LongFormatLayout, LongFormatLayoutOptions,
+    EntryClassification, FileDetails, FileIdentity, LongColumn,
+    LongFormatFileType, LongFormatLayout, LongFormatLayoutOptions,
 };
 use crate::structs::{AttributeDisplay, NameStyle, Params, PermissionDisplay};
 
@@ -129,6 +132,74 @@ pub(crate) fn classify_entry(
     }
 }
 
+/// Return the volume serial number and file index a path resolves to.
+pub(crate) fn file_identity(path: &Path) -> io::Result<FileIdentity> {
+    file_information(path).map(|info| information_identity(&info))
+}
+
+/// Return the identity shared by every hard link to a multiply-linked file.
+pub(crate) fn hardlink_identity(
+    path: &Path,
+    metadata: &fs::Metadata,
+) -> Option<FileIdentity> {
+    if metadata.is_dir() {
+        return None;
+    }
+
+    let info = file_information(path).ok()?;
+    (info.nNumberOfLinks > 1).then(|| information_identity(&info))
+}
+
+/// Return the bytes allocated on disk for an entry.
+///
+/// Compressed and sparse files report their stored size; anything the query
+/// cannot answer falls back to the apparent size.
+pub(crate) fn allocated_size(path: &Path, metadata: &fs::Metadata) -> u64 {
+    let Some(wide) = extended_find_path(path) else {
+        return metadata.len();
+    };
+    let mut high = 0;
+    // SAFETY: `wide` is NUL-terminated and `high` is a valid output location.
+    let low = unsafe { GetCompressedFileSizeW(wide.as_ptr(), &mut high) };
+    if low == INVALID_FILE_SIZE
+        && io::Error::last_os_error().raw_os_error() != Some(0)
+    {
+        return metadata.len();
+    }
+
+    (u64::from(high) << 32) | u64::from(low)
+}
+
+fn information_identity(info: &BY_HANDLE_FILE_INFORMATION) -> FileIdentity {
+    FileIdentity {
+        device: u64::from(info.dwVolumeSerialNumber),
+        inode: (u64::from(info.nFileIndexHigh) << 32)
+            | u64::from(info.nFileIndexLow),
+    }
+}
+
+fn file_information(path: &Path) -> io::Result<BY_HANDLE_FILE_INFORMATION> {
+    // Directories can only be opened with backup semantics; no access rights
+    // are needed to query the file index.
+    let file = fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)?;
+    // SAFETY: `BY_HANDLE_FILE_INFORMATION` is plain data, so the all-zero bit
+    // pattern is a valid value.
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+
+    // SAFETY: the handle is owned by `file` and stays open for the call, and
+    // `info` is a valid output buffer of the expected type.
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) }
+        == 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(info)
+}
+
 pub(crate) fn metadata_file_type(
     path: &Path,
     metadata: &fs::Metadata,
@@ -148,6 +219,13 @@ pub(crate) fn metadata_file_type(
     )
 }
 
+/// Convert raw path bytes read from a pipe into a path.
+///
+/// Windows paths are not byte strings, so piped input is decoded as UTF-8.
+pub(crate) fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
 pub(crate) fn entry_name_is_hidden(_name: &OsStr) -> bool {
     false
 }
@@ -189,6 +267,7 @@ pub(crate) fn file_details(
     metadata: &fs::Metadata,
     classification: EntryClassification,
     attribute_display: AttributeDisplay,
+    _numeric_ids: bool,
 ) -> FileDetails {
     FileDetails {
         file_type: classification.file_type.as_char().to_string(),
@@ -272,6 +351,11 @@ pub(crate) fn default_config_path() -> Option<PathBuf> {
     Some(path)
 }
 
+/// Return this machine's host name for `file://` links, or an empty string.
+pub(crate) fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
+
 pub(crate) fn normalize_path(path: PathBuf) -> PathBuf {
     let wide = path.as_os_str().encode_wide().collect::<Vec<_>>();
     let prefix_len = if wide.starts_with(EXTENDED_PATH_PREFIX) {
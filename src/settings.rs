@@ -2,18 +2,19 @@
 //!
 //! `lsplus` reads runtime display options from
 //! `~/.config/lsplus/config.toml`; [`COMPAT_MODE_ENV_VAR`] overrides config
-//! compatibility mode.
+//! compatibility mode and [`COLOR_ENV_VAR`] overrides config color.
 
 use std::ffi::OsString;
 use std::path::PathBuf;
 
+use clap::ValueEnum;
 use config::{Config, File, FileFormat};
 use serde::Deserialize;
 
 use crate::Params;
 use crate::cli::CompatMode;
 use crate::platform;
-use crate::structs::RawParams;
+use crate::structs::{ColorDisplay, RawParams};
 
 /// Environment variable that forces the startup compatibility mode.
 ///
@@ -21,6 +22,12 @@ use crate::structs::RawParams;
 /// file.
 pub const COMPAT_MODE_ENV_VAR: &str = "LSP_COMPAT_MODE";
 
+/// Environment variable that selects when output is colored.
+///
+/// When set, this takes precedence over the `color` and `no_color` values in
+/// the config file; `--color` and `--no-color` still override it.
+pub const COLOR_ENV_VAR: &str = "LSP_COLOR";
+
 /// Environment variable that overrides the config-file path.
 ///
 /// When set to a non-empty value, this takes precedence over the platform
@@ -71,8 +78,16 @@ pub(crate) fn config_path_from_home(home: Option<PathBuf>) -> Option<PathBuf> {
 /// Load runtime parameters from the default config file path.
 ///
 /// Invalid or missing config files fall back to the default [`Params`] values.
+///
+/// An invalid [`COLOR_ENV_VAR`] value is ignored here; startup loading
+/// reports it instead.
 pub fn load_config() -> Params {
-    load_config_from_path(config_path())
+    let mut params = load_config_from_path(config_path());
+    let _ = apply_color_env(
+        &mut params,
+        std::env::var(COLOR_ENV_VAR).ok().as_deref(),
+    );
+    params
 }
 
 /// Load runtime parameters from an explicit config path.
@@ -88,10 +103,36 @@ pub(crate) fn load_config_from_path(config_path: Option<PathBuf>) -> Params {
 /// `compat_mode` setting in the config file, and falls back to native mode
 /// when neither source is set.
 pub fn load_startup_config() -> Result<StartupConfig, String> {
-    load_startup_config_from(
+    let mut startup = load_startup_config_from(
         config_path(),
         std::env::var(COMPAT_MODE_ENV_VAR).ok(),
-    )
+    )?;
+    apply_color_env(
+        &mut startup.params,
+        std::env::var(COLOR_ENV_VAR).ok().as_deref(),
+    )?;
+    Ok(startup)
+}
+
+/// Apply a [`COLOR_ENV_VAR`] value over the color settings from config.
+pub(crate) fn apply_color_env(
+    params: &mut Params,
+    env_color: Option<&str>,
+) -> Result<(), String> {
+    let Some(value) = env_color else {
+        return Ok(());
+    };
+
+    let color = ColorDisplay::from_str(value.trim(), true).map_err(|_| {
+        format!(
+            "invalid {} value: unsupported color mode `{}`; expected \
+             `auto`, `always`, or `never`",
+            COLOR_ENV_VAR, value
+        )
+    })?;
+    params.color = color;
+    params.no_color = false;
+    Ok(())
 }
 
 /// Load startup configuration from explicit config and environment sources.
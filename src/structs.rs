@@ -4,6 +4,7 @@ use config::Config;
 use serde::Deserialize;
 use std::convert::From;
 use std::path::PathBuf;
+use std::thread;
 use std::time::SystemTime;
 
 use crate::cli;
@@ -11,6 +12,7 @@ use crate::utils::format::SizeScale;
 
 const NOISY_DIR_PRESET: [&str; 5] =
     [".git", ".hg", ".svn", "node_modules", "__pycache__"];
+const MAX_AUTO_THREADS: usize = 8;
 
 /// Entry-name indicator styles supported by `lsplus`.
 ///
@@ -76,6 +78,87 @@ pub enum ShortFormat {
     Vertical,
     /// Fill entries across variable-width rows.
     Across,
+    /// Separate entries with commas, wrapping at the terminal width.
+    Commas,
+    /// Print one entry per line.
+    SingleColumn,
+}
+
+/// GNU quoting styles for names, symlink targets, and section headers.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+pub enum QuotingStyle {
+    /// Print names as they are.
+    Literal,
+    /// Quote names for the shell when they contain special characters.
+    Shell,
+    /// Quote every name for the shell.
+    ShellAlways,
+    /// Quote like `shell`, writing control characters as `$'\n'` escapes.
+    ShellEscape,
+    /// Wrap names in double quotes with C escapes.
+    C,
+    /// Use C escapes without quotes, escaping spaces too.
+    Escape,
+    /// Wrap names in locale quotation marks with C escapes.
+    Locale,
+}
+
+/// Branch drawing styles for tree output.
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+pub enum TreeStyle {
+    /// Unicode branches below a root-less first level.
+    #[default]
+    Compact,
+    /// Unicode branches from a root row, like `tree`.
+    Classic,
+    /// ASCII branches below a root-less first level.
+    Ascii,
+    /// Plain whitespace indentation.
+    Indent,
+}
+
+impl TreeStyle {
+    /// Return whether the operand gets its own row with branches below it.
+    pub(crate) fn draws_root(self) -> bool {
+        matches!(self, Self::Classic)
+    }
+
+    /// Return the connector drawn before an entry name.
+    pub(crate) fn branch(self, is_last: bool) -> &'static str {
+        match (self, is_last) {
+            (Self::Compact | Self::Classic, false) => "├── ",
+            (Self::Compact | Self::Classic, true) => "└── ",
+            (Self::Ascii, false) => "|-- ",
+            (Self::Ascii, true) => "`-- ",
+            (Self::Indent, _) => "    ",
+        }
+    }
+
+    /// Return the prefix continuing an entry's branch past its children.
+    pub(crate) fn continuation(self, is_last: bool) -> &'static str {
+        match (self, is_last) {
+            (Self::Compact | Self::Classic, false) => "│   ",
+            (Self::Ascii, false) => "|   ",
+            _ => "    ",
+        }
+    }
+}
+
+/// How `--total-size` measures directories and the entries inside them.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Hash, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+pub enum TotalSize {
+    /// Sum file lengths, like `du --apparent-size`.
+    Apparent,
+    /// Sum allocated disk blocks, like `du`.
+    Disk,
 }
 
 /// Ordering modes for directory entries.
@@ -100,6 +183,43 @@ pub enum SortMode {
     None,
 }
 
+/// Controls which symlinks are replaced by the entries they point to.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+pub enum Dereference {
+    /// List symlinks themselves and never traverse through them.
+    #[default]
+    Never,
+    /// Follow symlinks named on the command line only.
+    CommandLine,
+    /// Follow every symlink, including during recursive and tree traversal.
+    Always,
+}
+
+impl Dereference {
+    /// Return whether command-line operands should be dereferenced.
+    pub(crate) fn follows_operands(self) -> bool {
+        matches!(self, Self::CommandLine | Self::Always)
+    }
+
+    /// Return whether directory entries should be dereferenced.
+    pub(crate) fn follows_entries(self) -> bool {
+        matches!(self, Self::Always)
+    }
+}
+
 /// Controls when file and directory icons are displayed.
 #[derive(
     Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ValueEnum,
@@ -126,8 +246,40 @@ impl IconDisplay {
     }
 }
 
+/// Controls when output is colored and styled.
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+pub enum ColorDisplay {
+    /// Color output when stdout is a terminal.
+    #[default]
+    Auto,
+    /// Color output even when stdout is redirected.
+    Always,
+    /// Never color output.
+    Never,
+}
+
+/// Controls when rendered names link to their files.
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+pub enum HyperlinkDisplay {
+    /// Link names when stdout is a terminal.
+    Auto,
+    /// Link names even when stdout is redirected.
+    Always,
+    /// Never link names.
+    #[default]
+    Never,
+}
+
 /// Runtime options after CLI flags and config defaults have been merged.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Params {
     /// Show entries whose names start with `.`.
     pub show_all: bool,
@@ -145,28 +297,79 @@ pub struct Params {
     pub long_format: bool,
     /// Force a short-format layout instead of selecting one from stdout.
     pub short_format: Option<ShortFormat>,
+    /// Short-format line width; detected when unset, unlimited when 0.
+    pub width: Option<usize>,
+    /// Tab stop spacing for short-format padding; spaces when unset or 0.
+    pub tabsize: Option<usize>,
     /// Show a title row in long-format output.
     pub header: bool,
+    /// Show numeric user and group IDs instead of looking up names.
+    pub numeric_uid_gid: bool,
+    /// Omit the owner column from long-format output.
+    pub no_owner: bool,
+    /// Omit the group column from long-format output.
+    pub no_group: bool,
     /// Render human-readable file sizes in long format.
     pub human_readable: bool,
     /// Use decimal powers for human-readable file sizes.
     pub si: bool,
+    /// Show recursive directory totals in the size column.
+    pub total_size: Option<TotalSize>,
+    /// List directory operands themselves instead of their contents.
+    pub directory: bool,
+    /// Emit Emacs dired offsets after long-format output.
+    pub dired: bool,
+    /// End each output line with NUL instead of a newline.
+    pub zero: bool,
     /// Recurse into child directories.
     pub recursive: bool,
     /// Render long-format tree output.
     pub tree: bool,
     /// Maximum visible entry depth for recursive/tree output.
     pub tree_level: usize,
+    /// Select how tree branches are drawn.
+    pub tree_style: TreeStyle,
+    /// Print entry totals after recursive and tree output.
+    pub summary: bool,
+    /// Render a disk-usage tree with shares of each parent directory.
+    pub usage: bool,
+    /// Merge single-child directory chains into one tree row.
+    pub collapse: bool,
+    /// Omit tree directories with no visible descendants.
+    pub prune_empty: bool,
+    /// Show only directories in trees, each with its child counts.
+    pub dirs_only: bool,
+    /// Most entries to list per directory in tree and recursive output.
+    pub max_entries: Option<usize>,
     /// Optional maximum depth for recursive output.
     pub recursive_level: Option<usize>,
     /// Directory basenames to skip while traversing recursive/tree output.
     pub prune_dirs: Vec<String>,
+    /// Select which symlinks are followed while listing and traversing.
+    pub dereference: Dereference,
+    /// Do not descend into directories on other filesystems.
+    pub one_file_system: bool,
+    /// Threads used to read directories while traversing; `0` picks a count
+    /// from the available parallelism.
+    pub threads: usize,
     /// Select when file and directory icons are displayed.
     pub icons: IconDisplay,
     /// Disable file and directory icons.
     pub no_icons: bool,
+    /// Select when output is colored; `Auto` is resolved from stdout before
+    /// rendering.
+    pub color: ColorDisplay,
     /// Disable colored or styled output.
     pub no_color: bool,
+    /// Select when names are OSC 8 links to their files; `Auto` is resolved
+    /// from stdout before rendering.
+    pub hyperlink: HyperlinkDisplay,
+    /// Quote names, link targets, and headers; `None` picks a style from
+    /// stdout.
+    pub quoting_style: Option<QuotingStyle>,
+    /// Print `?` for control characters the quoting style leaves raw; `None`
+    /// hides them only on terminals.
+    pub hide_control_chars: Option<bool>,
     /// Color file type and permission bits in long-format output.
     pub permission_colors: bool,
     /// Select which permission fields to show in long-format output.
@@ -179,6 +382,8 @@ pub struct Params {
     pub size_colors: bool,
     /// Dim paths matched by `.gitignore` rules.
     pub gitignore: bool,
+    /// Hide and prune entries matched by `.lspignore` rules.
+    pub lspignore: bool,
     /// Render humanized relative timestamps.
     pub fuzzy_time: bool,
 }
@@ -194,23 +399,47 @@ impl Default for Params {
             almost_all: false,
             long_format: false,
             short_format: None,
+            width: None,
+            tabsize: None,
             header: false,
+            numeric_uid_gid: false,
+            no_owner: false,
+            no_group: false,
             human_readable: false,
             si: false,
+            total_size: None,
+            directory: false,
+            dired: false,
+            zero: false,
             recursive: false,
             tree: false,
             tree_level: 2,
+            tree_style: TreeStyle::Compact,
+            summary: false,
+            usage: false,
+            collapse: false,
+            prune_empty: false,
+            dirs_only: false,
+            max_entries: None,
             recursive_level: None,
             prune_dirs: Vec::new(),
+            dereference: Dereference::Never,
+            one_file_system: false,
+            threads: 0,
             icons: IconDisplay::Auto,
             no_icons: false,
+            color: ColorDisplay::Auto,
             no_color: false,
+            hyperlink: HyperlinkDisplay::Never,
+            quoting_style: None,
+            hide_control_chars: None,
             permission_colors: true,
             permissions: PermissionDisplay::Symbolic,
             attributes: AttributeDisplay::Long,
             time_gradient: true,
             size_colors: true,
             gitignore: false,
+            lspignore: true,
             fuzzy_time: false,
         }
     }
@@ -227,23 +456,42 @@ pub(crate) struct RawParams {
     almost_all: bool,
     long_format: bool,
     short_format: Option<ShortFormat>,
+    width: Option<usize>,
+    tabsize: Option<usize>,
     header: bool,
+    numeric_uid_gid: bool,
+    no_group: bool,
     human_readable: bool,
     si: bool,
+    total_size: Option<TotalSize>,
     recursive: bool,
     tree: bool,
     tree_level: Option<usize>,
+    tree_style: TreeStyle,
+    summary: bool,
+    usage: bool,
+    collapse: bool,
+    prune_empty: bool,
+    dirs_only: bool,
+    max_entries: Option<usize>,
     prune_noisy_dirs: bool,
     prune_dirs: Vec<String>,
+    dereference: Dereference,
+    one_file_system: bool,
+    threads: usize,
     icons: Option<IconDisplay>,
     no_icons: bool,
+    color: Option<ColorDisplay>,
     no_color: bool,
+    hyperlink: HyperlinkDisplay,
+    quoting_style: Option<QuotingStyle>,
     permission_colors: Option<bool>,
     permissions: PermissionDisplay,
     attributes: AttributeDisplay,
     time_gradient: Option<bool>,
     size_colors: Option<bool>,
     gitignore: bool,
+    lspignore: Option<bool>,
     fuzzy_time: bool,
     indicator_style: Option<IndicatorStyle>,
     append_slash: Option<bool>,
@@ -273,6 +521,15 @@ pub enum NameStyle {
     BlockDevice,
 }
 
+/// A listed row with no single filesystem entry behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntheticEntry {
+    /// A path named by `--from-stdin` that does not exist on disk.
+    Virtual,
+    /// Stands in for this many entries left out by `--max-entries`.
+    Elided(usize),
+}
+
 impl From<Config> for Params {
     fn from(settings: Config) -> Self {
         settings
@@ -288,6 +545,10 @@ impl From<RawParams> for Params {
             Some(icons) => (icons, false),
             None => (IconDisplay::default(), raw.no_icons),
         };
+        let (color, no_color) = match raw.color {
+            Some(color) => (color, false),
+            None => (ColorDisplay::default(), raw.no_color),
+        };
 
         Self {
             show_all: raw.show_all,
@@ -304,26 +565,50 @@ impl From<RawParams> for Params {
             almost_all: raw.almost_all,
             long_format: raw.long_format,
             short_format: raw.short_format,
+            width: raw.width,
+            tabsize: raw.tabsize,
             header: raw.header,
+            numeric_uid_gid: raw.numeric_uid_gid,
+            no_owner: false,
+            no_group: raw.no_group,
             human_readable: raw.human_readable,
             si: raw.si,
+            total_size: raw.total_size,
+            directory: false,
+            dired: false,
+            zero: false,
             recursive: raw.recursive,
             tree: raw.tree,
             tree_level: normalized_tree_level(raw.tree_level),
+            tree_style: raw.tree_style,
+            summary: raw.summary,
+            usage: raw.usage,
+            collapse: raw.collapse,
+            prune_empty: raw.prune_empty,
+            dirs_only: raw.dirs_only,
+            max_entries: raw.max_entries.filter(|limit| *limit > 0),
             recursive_level: raw.tree_level.filter(|level| *level > 0),
             prune_dirs: configured_prune_dirs(
                 raw.prune_noisy_dirs,
                 raw.prune_dirs,
             ),
+            dereference: raw.dereference,
+            one_file_system: raw.one_file_system,
+            threads: raw.threads,
             icons,
             no_icons,
-            no_color: raw.no_color,
+            color,
+            no_color,
+            hyperlink: raw.hyperlink,
+            quoting_style: raw.quoting_style,
+            hide_control_chars: None,
             permission_colors: raw.permission_colors.unwrap_or(true),
             permissions: raw.permissions,
             attributes: raw.attributes,
             time_gradient: raw.time_gradient.unwrap_or(true),
             size_colors: raw.size_colors.unwrap_or(true),
             gitignore: raw.gitignore,
+            lspignore: raw.lspignore.unwrap_or(true),
             fuzzy_time: raw.fuzzy_time,
         }
     }
@@ -336,19 +621,31 @@ impl Params {
     ///
     /// CLI `--no-*` flags disable long-format accent defaults from config.
     /// Explicit CLI indicator flags override the config indicator style.
+    /// `-L` and `-H` can only widen the configured symlink dereferencing.
     ///
     /// Directory grouping and reverse ordering are disabled when the resolved
     /// sort mode is `None`, matching GNU `--sort=none` / `-U` behavior. `-f`
     /// also selects `None` unless a later sort selector overrides it.
     pub fn merge(flags: &cli::Flags, config: &Self) -> Self {
         let icons = flags.icons.unwrap_or(config.icons);
+        // Like GNU `--zero`, NUL-terminated output defaults to plain names
+        // one per line, since it is meant for other programs.
         let no_icons = if flags.icons.is_some() {
             false
         } else {
-            flags.no_icons || config.no_icons
+            flags.no_icons || config.no_icons || flags.zero
         };
 
-        let sort = flags.sort.unwrap_or(config.sort);
+        let usage = flags.usage || config.usage;
+        // Usage trees read best largest-first unless a sort is requested.
+        let sort =
+            flags
+                .sort
+                .unwrap_or(if usage && config.sort == SortMode::Name {
+                    SortMode::Size
+                } else {
+                    config.sort
+                });
         let sort_enabled = sort != SortMode::None;
 
         Self {
@@ -363,23 +660,64 @@ impl Params {
             almost_all: flags.almost_all || config.almost_all,
             long_format: flags.long
                 || flags.tree
+                || flags.dired
                 || config.long_format
                 || config.tree,
-            short_format: flags.short_format.or(config.short_format),
+            short_format: flags
+                .short_format
+                .or(flags.zero.then_some(ShortFormat::SingleColumn))
+                .or(config.short_format),
+            width: flags.width.or(config.width),
+            tabsize: flags.tabsize.or(config.tabsize),
             header: flags.header || config.header,
+            numeric_uid_gid: flags.numeric_uid_gid || config.numeric_uid_gid,
+            no_owner: flags.no_owner || config.no_owner,
+            no_group: flags.no_group || config.no_group,
             human_readable: flags.si
                 || flags.human_readable
                 || config.si
                 || config.human_readable,
             si: flags.si || config.si,
+            total_size: flags
+                .total_size
+                .or(config.total_size)
+                .or(usage.then_some(TotalSize::Apparent)),
+            directory: flags.directory,
+            dired: flags.dired,
+            zero: flags.zero,
             recursive: flags.recursive || config.recursive,
             tree: flags.tree || config.tree,
             tree_level: flags.tree_level.unwrap_or(config.tree_level),
+            tree_style: flags.tree_style.unwrap_or(config.tree_style),
+            summary: flags.summary || config.summary,
+            usage,
+            collapse: flags.collapse || config.collapse,
+            prune_empty: flags.prune_empty || config.prune_empty,
+            dirs_only: flags.dirs_only || config.dirs_only,
+            max_entries: flags.max_entries.or(config.max_entries),
             recursive_level: flags.tree_level.or(config.recursive_level),
             prune_dirs: merged_prune_dirs(flags, config),
+            dereference: merged_dereference(flags, config),
+            one_file_system: flags.one_file_system || config.one_file_system,
+            threads: flags.threads.unwrap_or(config.threads),
             icons,
             no_icons,
-            no_color: flags.no_color || config.no_color,
+            color: flags.color.unwrap_or(config.color),
+            // `--zero` only turns color off when no `color` setting or
+            // `LSP_COLOR` asked for it explicitly.
+            no_color: flags.color.is_none()
+                && (flags.no_color
+                    || config.no_color
+                    || (flags.zero && config.color == ColorDisplay::Auto)),
+            hyperlink: flags.hyperlink.unwrap_or(config.hyperlink),
+            quoting_style: flags
+                .quoting_style
+                .or(flags.zero.then_some(QuotingStyle::Literal))
+                .or(config.quoting_style),
+            hide_control_chars: flags
+                .hide_control_chars
+                .or(flags.zero.then_some(false))
+                .or(config.hide_control_chars),
             permission_colors: config.permission_colors
                 && !flags.no_permission_colors,
             permissions: flags.permissions.unwrap_or(config.permissions),
@@ -387,6 +725,7 @@ impl Params {
             time_gradient: config.time_gradient && !flags.no_time_gradient,
             size_colors: config.size_colors && !flags.no_size_colors,
             gitignore: flags.gitignore || config.gitignore,
+            lspignore: config.lspignore && !flags.no_lspignore,
             fuzzy_time: flags.fuzzy_time || config.fuzzy_time,
         }
     }
@@ -401,6 +740,42 @@ impl Params {
             || !self.icons.is_enabled(is_terminal, is_regular_file);
     }
 
+    /// Resolve automatic name links for the active stdout destination.
+    pub(crate) fn resolve_hyperlink_output(&mut self, is_terminal: bool) {
+        if self.hyperlink == HyperlinkDisplay::Auto {
+            self.hyperlink = if is_terminal {
+                HyperlinkDisplay::Always
+            } else {
+                HyperlinkDisplay::Never
+            };
+        }
+    }
+
+    /// Resolve GNU's quoting defaults for the active stdout destination.
+    ///
+    /// Terminals get `shell-escape` quoting with control characters hidden;
+    /// redirected output prints names literally.
+    pub(crate) fn resolve_quoting_output(&mut self, is_terminal: bool) {
+        self.quoting_style.get_or_insert(if is_terminal {
+            QuotingStyle::ShellEscape
+        } else {
+            QuotingStyle::Literal
+        });
+        self.hide_control_chars.get_or_insert(is_terminal);
+    }
+
+    /// Return how many threads should read directories while traversing.
+    ///
+    /// An automatic count follows the available parallelism but stays small,
+    /// since directory reads are bound by the filesystem rather than the CPU.
+    pub(crate) fn traversal_threads(&self) -> usize {
+        match self.threads {
+            0 => thread::available_parallelism()
+                .map_or(1, |threads| threads.get().min(MAX_AUTO_THREADS)),
+            threads => threads,
+        }
+    }
+
     /// Return the size scaling mode for long-format output.
     pub fn size_scale(&self) -> Option<SizeScale> {
         if self.si {
@@ -445,6 +820,16 @@ fn merged_prune_dirs(flags: &cli::Flags, config: &Params) -> Vec<String> {
     prune_dirs
 }
 
+fn merged_dereference(flags: &cli::Flags, config: &Params) -> Dereference {
+    if flags.dereference {
+        Dereference::Always
+    } else if flags.dereference_command_line {
+        config.dereference.max(Dereference::CommandLine)
+    } else {
+        config.dereference
+    }
+}
+
 fn append_prune_names<'a>(
     prune_dirs: &mut Vec<String>,
     names: impl IntoIterator<Item = &'a str>,
@@ -492,4 +877,9 @@ pub struct FileInfo {
     pub dimmed: bool,
     /// Full path used for metadata lookups and special display cases.
     pub full_path: PathBuf,
+    /// Whether `--one-file-system` stopped at this directory.
+    pub mount_point: bool,
+    /// Set when the row does not describe a real entry, so its long-format
+    /// metadata columns are unknown or only partly meaningful.
+    pub synthetic: Option<SyntheticEntry>,
 }
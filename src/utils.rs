@@ -4,14 +4,19 @@
 //! selection, icon lookup, and small formatting helpers shared by the CLI app.
 
 pub mod color;
+pub(crate) mod dired;
+pub(crate) mod disk_usage;
 pub mod file;
 pub mod format;
 pub mod fuzzy_time;
 pub mod gitignore;
+pub(crate) mod hyperlink;
 pub mod icons;
+pub(crate) mod quoting;
 pub mod render;
 pub(crate) mod sort;
 pub mod table;
 pub(crate) mod time;
+pub(crate) mod walk;
 
 pub use fuzzy_time::fuzzy_time;
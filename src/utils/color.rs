@@ -1,33 +1,64 @@
 //! Terminal color-mode detection and thread-local color configuration.
 //!
 //! Short-format names use the `colored_text` thread-local color mode.
-//! Long-format accent colors use the same terminal capability detection.
+//! Long-format accents read the color level back from the same configuration,
+//! which is resolved once at startup so both always agree.
 
-use colored_text::{ColorLevel, ColorMode, ColorizeConfig, RenderTarget};
+use colored_text::{
+    ColorDepthMode, ColorLevel, ColorMode, ColorizeConfig, RenderTarget,
+};
 
 use crate::Params;
+use crate::structs::ColorDisplay;
 
 /// Return the color mode implied by runtime parameters.
 pub(crate) fn color_mode_for(params: &Params) -> ColorMode {
     if params.no_color {
-        ColorMode::Never
-    } else {
-        ColorMode::Auto
+        return ColorMode::Never;
+    }
+
+    match params.color {
+        ColorDisplay::Auto => ColorMode::Auto,
+        ColorDisplay::Always => ColorMode::Always,
+        ColorDisplay::Never => ColorMode::Never,
     }
 }
 
-/// Apply the runtime color setting to the current thread.
-pub(crate) fn configure_color_output(params: &Params) {
+/// Resolve the color level for stdout once and apply it to the current
+/// thread.
+///
+/// `colored_text` does the detection, so `CLICOLOR_FORCE` colors redirected
+/// `auto` output, `NO_COLOR` disables color in every mode, and `FORCE_COLOR`
+/// or the terminal type picks the depth. The level is then pinned, and
+/// `params` is left with an explicit `always` or `never`.
+pub(crate) fn configure_color_output(params: &mut Params, is_terminal: bool) {
+    ColorizeConfig::set_color_depth_mode(ColorDepthMode::Auto);
+    ColorizeConfig::set_color_mode(color_mode_for(params));
+    let level =
+        ColorizeConfig::color_level(RenderTarget::Terminal(is_terminal));
+
+    params.no_color = level == ColorLevel::NoColor;
+    params.color = if params.no_color {
+        ColorDisplay::Never
+    } else {
+        ColorDisplay::Always
+    };
     ColorizeConfig::set_color_mode(color_mode_for(params));
+    ColorizeConfig::set_color_depth_mode(match level {
+        ColorLevel::NoColor => ColorDepthMode::NoColor,
+        ColorLevel::Ansi16 => ColorDepthMode::Ansi16,
+        ColorLevel::Ansi256 => ColorDepthMode::Ansi256,
+        ColorLevel::TrueColor => ColorDepthMode::TrueColor,
+    });
 }
 
 /// Detect the color capability for long-format accents.
 ///
-/// `params.no_color` is kept as an explicit guard so direct calls remain safe
+/// Disabled color is kept as an explicit guard so direct calls remain safe
 /// even before the thread-local color configuration has been applied on the
 /// calling thread.
 pub(crate) fn long_format_color_level(params: &Params) -> ColorLevel {
-    if params.no_color {
+    if color_mode_for(params) == ColorMode::Never {
         return ColorLevel::NoColor;
     }
 
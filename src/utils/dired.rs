@@ -0,0 +1,111 @@
+//! Byte offsets for Emacs dired, in the trailers `ls --dired` ends with.
+//!
+//! Long-format rows mark each entry name as the table writes it, so the
+//! offsets count the bytes actually written, styling included.
+
+use std::io::{self, Write};
+use std::ops::Range;
+
+use clap::ValueEnum;
+
+use crate::structs::{Params, QuotingStyle};
+use crate::utils::render::directory_header_text;
+use crate::utils::table::MarkedWrite;
+
+/// Spaces before every `--dired` line; dired keeps its marks in them.
+pub(crate) const DIRED_INDENT: usize = 2;
+
+/// Output that records the offsets of entry names and section headers.
+pub(crate) struct DiredWriter<W: Write> {
+    output: W,
+    /// Bytes written so far.
+    position: usize,
+    names: Vec<Range<usize>>,
+    headers: Vec<Range<usize>>,
+}
+
+impl<W: Write> DiredWriter<W> {
+    pub(crate) fn new(output: W) -> Self {
+        Self {
+            output,
+            position: 0,
+            names: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Write a section header, recording its path for `//SUBDIRED//`.
+    pub(crate) fn write_header(&mut self, header: &str) -> io::Result<()> {
+        let text = directory_header_text(header);
+        let start = self.position + DIRED_INDENT + leading_escape_len(&text);
+        self.headers.push(start..start + header.len());
+        writeln!(self, "{}{text}:", " ".repeat(DIRED_INDENT))
+    }
+
+    /// Write the offset trailers and the quoting style names were written in.
+    pub(crate) fn finish(mut self, params: &Params) -> io::Result<()> {
+        write_offsets(&mut self.output, "//DIRED//", &self.names)?;
+        write_offsets(&mut self.output, "//SUBDIRED//", &self.headers)?;
+        let style = params.quoting_style.unwrap_or(QuotingStyle::ShellEscape);
+        if let Some(value) = style.to_possible_value() {
+            writeln!(
+                self.output,
+                "//DIRED-OPTIONS// --quoting-style={}",
+                value.get_name()
+            )?;
+        }
+        self.output.flush()
+    }
+}
+
+impl<W: Write> Write for DiredWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.output.write(buf)?;
+        self.position += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+impl<W: Write> MarkedWrite for DiredWriter<W> {
+    fn mark(&mut self, span: Range<usize>) {
+        self.names
+            .push(self.position + span.start..self.position + span.end);
+    }
+}
+
+/// Return the length of the ANSI escape sequences `text` starts with.
+pub(crate) fn leading_escape_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut index = 0;
+    while bytes[index..].starts_with(b"\x1b[") {
+        let Some(end) = bytes[index + 2..]
+            .iter()
+            .position(|byte| (0x40..=0x7e).contains(byte))
+        else {
+            break;
+        };
+        index += end + 3;
+    }
+    index
+}
+
+/// Write one trailer line, leaving it out when there are no offsets.
+fn write_offsets(
+    output: &mut impl Write,
+    label: &str,
+    spans: &[Range<usize>],
+) -> io::Result<()> {
+    if spans.is_empty() {
+        return Ok(());
+    }
+
+    write!(output, "{label}")?;
+    for span in spans {
+        write!(output, " {} {}", span.start, span.end)?;
+    }
+    writeln!(output)
+}
@@ -0,0 +1,234 @@
+//! Recursive directory sizes for `--total-size`.
+//!
+//! Totals cover everything below a directory, including entries hidden from
+//! the listing, and count each multiply-linked file once per total, like
+//! `du`. Symlinks are measured as links and never followed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::Params;
+use crate::platform::{self, FileIdentity};
+use crate::structs::TotalSize;
+
+/// Return the size shown for an entry under the given total-size mode.
+///
+/// Directories report the total of their contents; other entries report
+/// their own size measured the same way.
+pub(crate) fn entry_size(
+    path: &Path,
+    metadata: &fs::Metadata,
+    total_size: TotalSize,
+    params: &Params,
+    cache: &UsageCache,
+) -> u64 {
+    if metadata.is_dir() {
+        directory_usage(
+            path,
+            metadata,
+            total_size,
+            params.one_file_system,
+            cache,
+        )
+        .total()
+    } else {
+        measured_size(path, metadata, total_size)
+    }
+}
+
+/// Sizes found below one directory, kept so a parent can reuse them.
+///
+/// Multiply-linked files are kept apart so a total that reaches the same
+/// file through two subdirectories still counts it once.
+#[derive(Debug, Default)]
+struct DirectoryUsage {
+    /// Everything except multiply-linked files.
+    unlinked: u64,
+    links: HashMap<FileIdentity, u64>,
+}
+
+impl DirectoryUsage {
+    fn total(&self) -> u64 {
+        self.unlinked + self.links.values().sum::<u64>()
+    }
+
+    fn add(&mut self, child: &DirectoryUsage) {
+        self.unlinked += child.unlinked;
+        for (identity, size) in &child.links {
+            self.links.entry(*identity).or_insert(*size);
+        }
+    }
+}
+
+/// Identifies a directory total; one-file-system totals stop at mounts.
+type UsageKey = (FileIdentity, TotalSize, bool);
+
+/// Directory totals already measured in one run.
+///
+/// Sorting, listing, `--max-entries`, and every level of a tree ask for the
+/// same subtrees, so each directory is read once and its total reused. A run
+/// makes one cache up front; clones share it, so traversal workers that sort
+/// by size fill the same totals.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct UsageCache {
+    totals: Arc<Mutex<HashMap<UsageKey, Arc<DirectoryUsage>>>>,
+}
+
+impl UsageCache {
+    fn get(&self, key: Option<UsageKey>) -> Option<Arc<DirectoryUsage>> {
+        self.totals().get(&key?).cloned()
+    }
+
+    fn insert(&self, key: Option<UsageKey>, usage: &Arc<DirectoryUsage>) {
+        if let Some(key) = key {
+            self.totals().insert(key, Arc::clone(usage));
+        }
+    }
+
+    /// Lock the totals; a panicked holder cannot leave a partial total
+    /// behind, so a poisoned lock is still safe to use.
+    fn totals(
+        &self,
+    ) -> MutexGuard<'_, HashMap<UsageKey, Arc<DirectoryUsage>>> {
+        self.totals.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A directory whose subdirectories are still being measured.
+struct UsageFrame {
+    key: Option<UsageKey>,
+    usage: DirectoryUsage,
+    subdirectories: Vec<(PathBuf, fs::Metadata)>,
+}
+
+/// Sum the sizes below a directory, bottom-up.
+///
+/// Apparent totals only count non-directory entries, since a directory's own
+/// length says nothing about its contents. Disk totals also count the blocks
+/// allocated to the directories themselves. Unreadable subdirectories are
+/// skipped, and subtrees measured earlier in the run are reused.
+fn directory_usage(
+    root: &Path,
+    root_metadata: &fs::Metadata,
+    total_size: TotalSize,
+    one_file_system: bool,
+    cache: &UsageCache,
+) -> Arc<DirectoryUsage> {
+    let usage_key = |path: &Path| {
+        platform::file_identity(path)
+            .ok()
+            .map(|identity| (identity, total_size, one_file_system))
+    };
+    let root_key = usage_key(root);
+    if let Some(usage) = cache.get(root_key) {
+        return usage;
+    }
+
+    let filesystem = root_key
+        .filter(|_| one_file_system)
+        .map(|(identity, ..)| identity.device);
+    let mut stack = vec![read_usage_frame(
+        root,
+        root_metadata,
+        root_key,
+        total_size,
+        filesystem,
+    )];
+
+    loop {
+        let frame = stack.last_mut().expect("usage stack holds the root");
+        if let Some((path, metadata)) = frame.subdirectories.pop() {
+            let key = usage_key(&path);
+            if let Some(usage) = cache.get(key) {
+                frame.usage.add(&usage);
+            } else if key.is_none_or(|key| {
+                stack.iter().all(|frame| frame.key != Some(key))
+            }) {
+                stack.push(read_usage_frame(
+                    &path, &metadata, key, total_size, filesystem,
+                ));
+            }
+            continue;
+        }
+
+        let frame = stack.pop().expect("usage stack holds the root");
+        let usage = Arc::new(frame.usage);
+        cache.insert(frame.key, &usage);
+        match stack.last_mut() {
+            Some(parent) => parent.usage.add(&usage),
+            None => return usage,
+        }
+    }
+}
+
+/// Measure the entries directly inside `directory` and list its
+/// subdirectories on the same filesystem.
+fn read_usage_frame(
+    directory: &Path,
+    metadata: &fs::Metadata,
+    key: Option<UsageKey>,
+    total_size: TotalSize,
+    filesystem: Option<u64>,
+) -> UsageFrame {
+    let mut usage = DirectoryUsage {
+        unlinked: directory_own_size(directory, metadata, total_size),
+        links: HashMap::new(),
+    };
+    let mut subdirectories = Vec::new();
+
+    for entry in fs::read_dir(directory).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let Ok(entry_metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+
+        if entry_metadata.is_dir() {
+            let other_filesystem = filesystem.is_some_and(|device| {
+                platform::file_identity(&path)
+                    .is_ok_and(|identity| identity.device != device)
+            });
+            if !other_filesystem {
+                subdirectories.push((path, entry_metadata));
+            }
+            continue;
+        }
+
+        let size = measured_size(&path, &entry_metadata, total_size);
+        match platform::hardlink_identity(&path, &entry_metadata) {
+            Some(identity) => {
+                usage.links.insert(identity, size);
+            }
+            None => usage.unlinked += size,
+        }
+    }
+
+    UsageFrame {
+        key,
+        usage,
+        subdirectories,
+    }
+}
+
+fn directory_own_size(
+    path: &Path,
+    metadata: &fs::Metadata,
+    total_size: TotalSize,
+) -> u64 {
+    match total_size {
+        TotalSize::Apparent => 0,
+        TotalSize::Disk => platform::allocated_size(path, metadata),
+    }
+}
+
+fn measured_size(
+    path: &Path,
+    metadata: &fs::Metadata,
+    total_size: TotalSize,
+) -> u64 {
+    match total_size {
+        TotalSize::Apparent => metadata.len(),
+        TotalSize::Disk => platform::allocated_size(path, metadata),
+    }
+}
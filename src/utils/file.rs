@@ -6,17 +6,25 @@
 //! entries.
 
 use colored_text::{Colorize, StyledText};
+use std::borrow::Cow;
 use std::ffi::OsString;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::IndicatorStyle;
 use crate::Params;
 use crate::platform::{self, EntryClassification, LongFormatFileType};
 use crate::structs::FileInfo;
-use crate::structs::NameStyle;
-use crate::utils::{self, gitignore::GitignoreCache};
+use crate::structs::{NameStyle, SyntheticEntry};
+use crate::utils::{
+    self,
+    disk_usage::UsageCache,
+    gitignore::{GitignoreCache, LspignoreCache},
+    quoting::{quote_name, quote_path},
+    walk::ElidedEntries,
+};
 
 /// Directory entry data captured before visibility filtering and sorting.
 pub(crate) struct DirectoryEntryData {
@@ -36,6 +44,16 @@ pub(crate) struct DirectoryEntryData {
 pub fn collect_file_names(
     path: &Path,
     params: &Params,
+) -> io::Result<Vec<String>> {
+    collect_file_names_with_usage(path, params, &UsageCache::default())
+}
+
+/// Like [`collect_file_names`], reusing directory totals measured earlier in
+/// the run when sorting by `--total-size`.
+pub(crate) fn collect_file_names_with_usage(
+    path: &Path,
+    params: &Params,
+    usage: &UsageCache,
 ) -> io::Result<Vec<String>> {
     let mut file_names = Vec::new();
 
@@ -56,7 +74,10 @@ pub fn collect_file_names(
                 entry_result.map(|entry| {
                     let path = entry.path();
                     let (metadata, classification_result) =
-                        match fs::symlink_metadata(&path) {
+                        match entry_metadata(
+                            &path,
+                            params.dereference.follows_entries(),
+                        ) {
                             Ok(metadata) => {
                                 let classification =
                                     platform::classify_entry(&path, &metadata);
@@ -75,7 +96,8 @@ pub fn collect_file_names(
             })
             .collect();
 
-        file_names.extend(collect_visible_file_names(path, entries, params));
+        file_names
+            .extend(collect_visible_file_names(path, entries, params, usage));
     }
     Ok(file_names)
 }
@@ -83,13 +105,17 @@ pub fn collect_file_names(
 /// Return visible entry names for a directory after sorting and filtering.
 ///
 /// Hidden-file handling follows the parsed params, and `dirs_first` preserves
-/// the sorted order within the directory and non-directory groups.
+/// the sorted order within the directory and non-directory groups. Entries
+/// matched by `.lspignore` rules are treated like dotfiles and only listed
+/// with `-a` or `-A`.
 pub(crate) fn collect_visible_file_names(
     path: &Path,
     entries: Vec<Result<DirectoryEntryData, io::Error>>,
     params: &Params,
+    usage: &UsageCache,
 ) -> Vec<String> {
     let mut visible_entries = Vec::new();
+    let mut lspignore_cache = LspignoreCache::default();
 
     for entry_result in entries {
         match entry_result {
@@ -100,7 +126,15 @@ pub(crate) fn collect_visible_file_names(
                         !platform::entry_name_is_hidden(&entry.file_name)
                     }
                 };
-                if params.show_all || params.almost_all || visible {
+                let show_hidden = params.show_all || params.almost_all;
+                if show_hidden
+                    || (visible
+                        && !is_lspignored(
+                            &entry,
+                            params,
+                            &mut lspignore_cache,
+                        ))
+                {
                     visible_entries.push(entry);
                 }
             }
@@ -108,7 +142,7 @@ pub(crate) fn collect_visible_file_names(
         }
     }
 
-    utils::sort::sort_entries(&mut visible_entries, params);
+    utils::sort::sort_entries(&mut visible_entries, params, usage);
 
     if params.dirs_first {
         let (dirs, files): (Vec<_>, Vec<_>) = visible_entries
@@ -138,6 +172,18 @@ pub(crate) fn collect_visible_file_names(
     file_names
 }
 
+fn is_lspignored(
+    entry: &DirectoryEntryData,
+    params: &Params,
+    lspignore_cache: &mut LspignoreCache,
+) -> bool {
+    params.lspignore
+        && lspignore_cache.is_ignored(
+            &entry.path,
+            entry.metadata.as_ref().is_some_and(fs::Metadata::is_dir),
+        )
+}
+
 /// Collect display metadata for a file or every visible entry in a directory.
 ///
 /// Directory symlinks are followed for directory traversal decisions, while
@@ -145,28 +191,41 @@ pub(crate) fn collect_visible_file_names(
 pub fn collect_file_info(
     path: &Path,
     params: &Params,
+) -> io::Result<Vec<FileInfo>> {
+    collect_file_info_with_usage(path, params, &UsageCache::default())
+}
+
+/// Like [`collect_file_info`], reusing directory totals measured earlier in
+/// the run.
+pub(crate) fn collect_file_info_with_usage(
+    path: &Path,
+    params: &Params,
+    usage: &UsageCache,
 ) -> io::Result<Vec<FileInfo>> {
     let mut file_info = Vec::new();
     let mut gitignore_cache = GitignoreCache::default();
-    let symlink_metadata = fs::symlink_metadata(path)?;
+    let metadata =
+        entry_metadata(path, params.dereference.follows_operands())?;
 
-    let classification = platform::classify_entry(path, &symlink_metadata);
+    let classification = platform::classify_entry(path, &metadata);
 
     if classification.display_as_directory {
-        let file_names = utils::file::collect_file_names(path, params)?;
+        let file_names = collect_file_names_with_usage(path, params, usage)?;
         append_file_info_for_names(
             &mut file_info,
             path,
             &file_names,
             params,
             &mut gitignore_cache,
+            usage,
         );
     } else {
         let info = create_file_info_from_metadata_with_gitignore(
             path,
-            &symlink_metadata,
+            &metadata,
             params,
             &mut gitignore_cache,
+            usage,
         );
         file_info.push(info);
     }
@@ -180,6 +239,7 @@ pub(crate) fn append_file_info_for_names(
     file_names: &[String],
     params: &Params,
     gitignore_cache: &mut GitignoreCache,
+    usage: &UsageCache,
 ) {
     if file_names.is_empty() {
         return;
@@ -189,8 +249,10 @@ pub(crate) fn append_file_info_for_names(
         let full_path = path.join(file_name);
         match create_file_info_with_gitignore(
             &full_path,
-            params,
+            &listed_entry_params(params, file_name),
+            params.dereference.follows_entries(),
             gitignore_cache,
+            usage,
         ) {
             Ok(mut info) => {
                 preserve_synthetic_dot_name(&mut info, file_name);
@@ -201,6 +263,29 @@ pub(crate) fn append_file_info_for_names(
     }
 }
 
+/// Return whether a listed name is a synthetic `.` or `..` entry.
+pub(crate) fn is_dot_entry(file_name: &str) -> bool {
+    matches!(file_name, "." | "..")
+}
+
+/// Return the params used to build one listed entry.
+///
+/// Synthetic `.` and `..` rows keep their metadata size under
+/// `--total-size`, since totalling `..` would measure the whole parent tree.
+pub(crate) fn listed_entry_params<'a>(
+    params: &'a Params,
+    file_name: &str,
+) -> Cow<'a, Params> {
+    if params.total_size.is_some() && is_dot_entry(file_name) {
+        Cow::Owned(Params {
+            total_size: None,
+            ..params.clone()
+        })
+    } else {
+        Cow::Borrowed(params)
+    }
+}
+
 /// Preserve the display identity of synthetic Unix `.` and `..` entries.
 ///
 /// `Path` normalizes these components while constructing `FileInfo`, so
@@ -209,30 +294,49 @@ pub(crate) fn preserve_synthetic_dot_name(
     info: &mut FileInfo,
     file_name: &str,
 ) {
-    if matches!(file_name, "." | "..") {
+    if is_dot_entry(file_name) {
         let file_name = String::from(file_name);
         info.short_name.clone_from(&file_name);
         info.display_name = file_name;
     }
 }
 
-/// Build display metadata for a single filesystem path.
+/// Build display metadata for a single command-line filesystem path.
 pub fn create_file_info(path: &Path, params: &Params) -> io::Result<FileInfo> {
+    create_file_info_with_usage(path, params, &UsageCache::default())
+}
+
+/// Like [`create_file_info`], reusing directory totals measured earlier in
+/// the run.
+pub(crate) fn create_file_info_with_usage(
+    path: &Path,
+    params: &Params,
+    usage: &UsageCache,
+) -> io::Result<FileInfo> {
     let mut gitignore_cache = GitignoreCache::default();
-    create_file_info_with_gitignore(path, params, &mut gitignore_cache)
+    create_file_info_with_gitignore(
+        path,
+        params,
+        params.dereference.follows_operands(),
+        &mut gitignore_cache,
+        usage,
+    )
 }
 
 pub(crate) fn create_file_info_with_gitignore(
     path: &Path,
     params: &Params,
+    follow_links: bool,
     gitignore_cache: &mut GitignoreCache,
+    usage: &UsageCache,
 ) -> io::Result<FileInfo> {
-    let metadata = fs::symlink_metadata(path)?;
+    let metadata = entry_metadata(path, follow_links)?;
     Ok(create_file_info_from_metadata_with_gitignore(
         path,
         &metadata,
         params,
         gitignore_cache,
+        usage,
     ))
 }
 
@@ -241,6 +345,7 @@ pub(crate) fn create_file_info_from_metadata_with_gitignore(
     metadata: &fs::Metadata,
     params: &Params,
     gitignore_cache: &mut GitignoreCache,
+    usage: &UsageCache,
 ) -> FileInfo {
     let classification = platform::classify_entry(path, metadata);
     let item_icon = if params.no_icons {
@@ -253,18 +358,10 @@ pub(crate) fn create_file_info_from_metadata_with_gitignore(
         metadata,
         classification,
         params.attributes,
+        params.numeric_uid_gid,
     );
 
-    let mut file_name = path
-        .file_name()
-        .map(|n| n.to_string_lossy().into_owned())
-        .unwrap_or_else(|| path.to_string_lossy().into_owned());
-
-    if file_name.starts_with("./") {
-        file_name = file_name.replacen("./", "", 1);
-    }
-
-    let safe_file_name = sanitize_for_terminal(&file_name);
+    let safe_file_name = quote_name(&entry_name(path), params);
     let indicated_file_name = format_name_with_indicator(
         &safe_file_name,
         path,
@@ -309,7 +406,11 @@ pub(crate) fn create_file_info_from_metadata_with_gitignore(
         nlink: details.nlink,
         user: details.user,
         group: details.group,
-        size: details.size,
+        size: params.total_size.map_or(details.size, |total_size| {
+            utils::disk_usage::entry_size(
+                path, metadata, total_size, params, usage,
+            )
+        }),
         mtime: details.mtime,
         item_icon,
         short_name,
@@ -317,9 +418,154 @@ pub(crate) fn create_file_info_from_metadata_with_gitignore(
         name_style,
         dimmed: ignored,
         full_path: path.to_path_buf(),
+        mount_point: false,
+        synthetic: None,
+    }
+}
+
+/// Return the name an entry is listed under, before quoting.
+pub(crate) fn entry_name(path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+
+    match file_name.strip_prefix("./") {
+        Some(name) => name.to_string(),
+        None => file_name,
+    }
+}
+
+/// Build display metadata for a listed path that does not exist on disk.
+///
+/// Only the name, icon, and directory styling are known; the renderer shows
+/// `?` in place of the metadata columns.
+pub(crate) fn create_virtual_file_info(
+    path: &Path,
+    directory: bool,
+    params: &Params,
+) -> FileInfo {
+    let (file_type, name_style) = if directory {
+        (LongFormatFileType::Directory, NameStyle::Directory)
+    } else {
+        (LongFormatFileType::Regular, NameStyle::Plain)
+    };
+    let item_icon = if params.no_icons {
+        None
+    } else {
+        Some(utils::icons::get_item_icon(file_type, path))
+    };
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+    let suffix = match params.indicator_style {
+        IndicatorStyle::None => "",
+        _ => slash_indicator_suffix(directory),
+    };
+    let short_name = format!("{}{suffix}", quote_name(&file_name, params));
+
+    FileInfo {
+        file_type: String::new(),
+        mode: String::new(),
+        mode_bits: 0,
+        nlink: 0,
+        user: String::new(),
+        group: String::new(),
+        size: 0,
+        mtime: SystemTime::UNIX_EPOCH,
+        item_icon,
+        display_name: colorize_name(&short_name, name_style, false),
+        short_name,
+        name_style,
+        dimmed: false,
+        full_path: path.to_path_buf(),
+        mount_point: false,
+        synthetic: Some(SyntheticEntry::Virtual),
     }
 }
 
+/// Build the dimmed row that stands in for entries left out of `directory`
+/// by `--max-entries`.
+pub(crate) fn create_elided_file_info(
+    directory: &Path,
+    elided: ElidedEntries,
+) -> FileInfo {
+    let noun = if elided.count == 1 {
+        "entry"
+    } else {
+        "entries"
+    };
+    let short_name = format!("… {} more {noun}", elided.count);
+
+    FileInfo {
+        file_type: String::new(),
+        mode: String::new(),
+        mode_bits: 0,
+        nlink: 0,
+        user: String::new(),
+        group: String::new(),
+        size: elided.bytes,
+        mtime: SystemTime::UNIX_EPOCH,
+        item_icon: None,
+        display_name: plain_text(short_name.as_str(), true),
+        short_name,
+        name_style: NameStyle::Plain,
+        dimmed: true,
+        full_path: directory.to_path_buf(),
+        mount_point: false,
+        synthetic: Some(SyntheticEntry::Elided(elided.count)),
+    }
+}
+
+/// Read the metadata used to list a path.
+///
+/// With `follow_links`, symlinks report their target's metadata; a broken link
+/// falls back to its own metadata so it stays listable.
+pub(crate) fn entry_metadata(
+    path: &Path,
+    follow_links: bool,
+) -> io::Result<fs::Metadata> {
+    let metadata = fs::symlink_metadata(path)?;
+    if follow_links && metadata.is_symlink() {
+        Ok(fs::metadata(path).unwrap_or(metadata))
+    } else {
+        Ok(metadata)
+    }
+}
+
+/// Mark a directory that `--one-file-system` will not descend into.
+///
+/// The marker follows the other long-format name annotations; short output
+/// appends it to the bare name through [`mount_point_marker`].
+pub(crate) fn mark_mount_point(info: &mut FileInfo) {
+    info.mount_point = true;
+    info.display_name.push_str(&mount_point_marker(info.dimmed));
+}
+
+/// Return the ` [Mount Point]` suffix, dimmed like the name it follows.
+pub(crate) fn mount_point_marker(dimmed: bool) -> String {
+    format!(
+        "{}{}",
+        plain_text(" ", dimmed),
+        apply_dim("[Mount Point]".magenta(), dimmed)
+    )
+}
+
+/// Append a directory's file and subdirectory counts to its tree row name.
+pub(crate) fn mark_child_counts(
+    info: &mut FileInfo,
+    files: usize,
+    directories: usize,
+) {
+    let file_noun = if files == 1 { "file" } else { "files" };
+    let directory_noun = if directories == 1 { "dir" } else { "dirs" };
+    info.display_name.push_str(&plain_text(
+        format!(" ({files} {file_noun}, {directories} {directory_noun})"),
+        true,
+    ));
+}
+
 /// Return the displayed name, preserving special styling for `.` and `..`.
 pub fn check_display_name(info: &FileInfo) -> String {
     match info.short_name.as_str() {
@@ -506,7 +752,7 @@ pub(crate) fn format_symlink_display_name_with_dim(
                 target
             };
             let target_path = platform::normalize_path(target_path);
-            let display_target = sanitize_path_for_terminal(&target_path);
+            let display_target = quote_path(&target_path, params);
             if params.long_format {
                 let display_target = fs::symlink_metadata(&target_path)
                     .map(|metadata| {
@@ -1,10 +1,13 @@
-//! Gitignore matcher discovery and caching.
+//! Gitignore and `.lspignore` matcher discovery and caching.
 //!
-//! The cache builds matchers from the worktree root to the listed directory so
-//! nested `.gitignore` files, `.git/info/exclude`, and global rules match Git
-//! order.
+//! The gitignore cache builds matchers from the worktree root to the listed
+//! directory so nested `.gitignore` files, `.git/info/exclude`, and global
+//! rules match Git order. The `.lspignore` cache uses the same gitignore
+//! syntax, but discovers files from the listed directory upward without
+//! requiring a Git worktree.
 
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -13,6 +16,9 @@ use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
 use crate::platform;
 
+/// File name of per-project `lsplus` ignore files.
+pub const LSPIGNORE_FILE_NAME: &str = ".lspignore";
+
 /// Cache of gitignore matchers keyed by listed directory.
 #[derive(Default)]
 pub struct GitignoreCache {
@@ -34,6 +40,89 @@ impl GitignoreCache {
     }
 }
 
+/// Cache of `.lspignore` matchers keyed by containing directory.
+#[derive(Default)]
+pub struct LspignoreCache {
+    matchers: HashMap<PathBuf, Option<LspignoreMatcher>>,
+}
+
+impl LspignoreCache {
+    /// Return whether a path is matched by `.lspignore` rules.
+    ///
+    /// Rules are read from the path's parent directory and every ancestor
+    /// above it. The nearest file that matches decides the result, so a
+    /// nested `!pattern` can re-include an entry ignored further up.
+    pub fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        let Some(file_name) = path.file_name() else {
+            return false;
+        };
+        let key = parent_directory(path).to_path_buf();
+        let matcher = self
+            .matchers
+            .entry(key.clone())
+            .or_insert_with(|| LspignoreMatcher::for_directory(&key));
+
+        matcher
+            .as_ref()
+            .is_some_and(|lspignore| lspignore.is_ignored(file_name, is_dir))
+    }
+}
+
+struct LspignoreMatcher {
+    directory: PathBuf,
+    /// Matchers ordered from the listed directory up to the filesystem root.
+    matchers: Vec<Gitignore>,
+}
+
+impl LspignoreMatcher {
+    fn for_directory(directory: &Path) -> Option<Self> {
+        let directory = normalize_path(directory.to_path_buf());
+        let matchers: Vec<_> = directory
+            .ancestors()
+            .filter_map(|ancestor| {
+                let ignore_file = ancestor.join(LSPIGNORE_FILE_NAME);
+                if !ignore_file.is_file() {
+                    return None;
+                }
+
+                let mut builder = GitignoreBuilder::new(ancestor);
+                let _ = builder.add(ignore_file);
+                Some(build_matcher_or_empty(&builder))
+            })
+            .collect();
+
+        if matchers.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            directory,
+            matchers,
+        })
+    }
+
+    fn is_ignored(&self, file_name: &OsStr, is_dir: bool) -> bool {
+        let path = self.directory.join(file_name);
+
+        for matcher in &self.matchers {
+            match matcher.matched_path_or_any_parents(&path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+
+        false
+    }
+}
+
+fn parent_directory(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
 struct GitignoreMatcher {
     root: PathBuf,
     gitignore_matcher: Gitignore,
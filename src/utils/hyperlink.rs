@@ -0,0 +1,58 @@
+//! OSC 8 hyperlinks that let terminals open rendered names.
+//!
+//! The escapes are zero-width, so [`visible_width`] and the short-format grid
+//! skip them like color codes.
+//!
+//! [`visible_width`]: crate::utils::table::visible_width
+
+use std::path::{self, Path};
+use std::sync::OnceLock;
+
+use crate::platform;
+use crate::structs::{HyperlinkDisplay, Params};
+
+/// Ends a link; an empty URI closes the one opened before it.
+pub(crate) const LINK_END: &str = "\x1b]8;;\x1b\\";
+
+/// Return whether names should be linked to their files.
+pub(crate) fn links_enabled(params: &Params) -> bool {
+    params.hyperlink == HyperlinkDisplay::Always
+}
+
+/// Wrap `text` in a link to `path` when hyperlinks are enabled.
+pub(crate) fn link_name(text: &str, path: &Path, params: &Params) -> String {
+    if links_enabled(params) {
+        format!("{}{text}{LINK_END}", link_start(path))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Return the escape that starts a link to `path`.
+pub(crate) fn link_start(path: &Path) -> String {
+    format!("\x1b]8;;{}\x1b\\", file_url(path))
+}
+
+/// Build a `file://host/abs/path` URL, percent-encoding reserved bytes.
+pub(crate) fn file_url(path: &Path) -> String {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+    let host = HOSTNAME.get_or_init(platform::hostname);
+    let absolute = path::absolute(path).unwrap_or_else(|_| path.into());
+    let bytes = absolute.as_os_str().as_encoded_bytes();
+
+    let mut url = format!("file://{host}");
+    // Windows drive paths such as `C:\dir` still need a leading slash.
+    if !bytes.starts_with(b"/") {
+        url.push('/');
+    }
+    for &byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => url.push(byte.into()),
+            b'-' | b'.' | b'_' | b'~' | b'/' => url.push(byte.into()),
+            b':' if cfg!(windows) => url.push(':'),
+            b'\\' if cfg!(windows) => url.push('/'),
+            _ => url.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    url
+}
@@ -0,0 +1,161 @@
+//! GNU-style quoting for entry names, symlink targets, and section headers.
+//!
+//! Each [`QuotingStyle`] mirrors the matching `ls --quoting-style` word, so
+//! names can be pasted back into a shell or read unambiguously.
+
+use std::path::Path;
+
+use crate::structs::{Params, QuotingStyle};
+
+/// Quote an entry name with the style resolved for the current output.
+pub(crate) fn quote_name(name: &str, params: &Params) -> String {
+    quote(
+        name,
+        params.quoting_style.unwrap_or(QuotingStyle::ShellEscape),
+        params.hide_control_chars.unwrap_or(true),
+    )
+}
+
+/// Quote a path, such as a section header or symlink target, as one name.
+pub(crate) fn quote_path(path: &Path, params: &Params) -> String {
+    quote_name(&path.to_string_lossy(), params)
+}
+
+/// Quote text with an explicit style.
+///
+/// `hide_control_chars` replaces control characters with `?` in the styles
+/// that would otherwise print them raw.
+pub(crate) fn quote(
+    text: &str,
+    style: QuotingStyle,
+    hide_control_chars: bool,
+) -> String {
+    match style {
+        QuotingStyle::Literal => hide_controls(text, hide_control_chars),
+        QuotingStyle::Shell => {
+            shell_quote(&hide_controls(text, hide_control_chars), false)
+        }
+        QuotingStyle::ShellAlways => {
+            shell_quote(&hide_controls(text, hide_control_chars), true)
+        }
+        QuotingStyle::ShellEscape => shell_escape(text),
+        QuotingStyle::C => format!("\"{}\"", c_escape(text, Some('"'))),
+        QuotingStyle::Escape => c_escape(text, Some(' ')),
+        QuotingStyle::Locale => format!("‘{}’", c_escape(text, None)),
+    }
+}
+
+fn hide_controls(text: &str, hide_control_chars: bool) -> String {
+    if hide_control_chars {
+        text.chars()
+            .map(|character| {
+                if character.is_control() {
+                    '?'
+                } else {
+                    character
+                }
+            })
+            .collect()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Return whether a character can appear in an unquoted shell word.
+fn is_shell_safe(character: char, first: bool) -> bool {
+    match character {
+        'a'..='z' | 'A'..='Z' | '0'..='9' => true,
+        '%' | '+' | ',' | '-' | '.' | '/' | ':' | '=' | '@' | '_' | '^' => {
+            true
+        }
+        '#' | '~' => !first,
+        _ => !character.is_ascii() && !character.is_control(),
+    }
+}
+
+fn needs_shell_quotes(text: &str) -> bool {
+    text.is_empty()
+        || text
+            .chars()
+            .enumerate()
+            .any(|(index, character)| !is_shell_safe(character, index == 0))
+}
+
+fn shell_quote(text: &str, always: bool) -> String {
+    if !always && !needs_shell_quotes(text) {
+        return text.to_string();
+    }
+
+    if text.contains('\'') && !text.contains(['"', '$', '`', '\\', '!']) {
+        format!("\"{text}\"")
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+/// Quote like `shell`, writing runs of control characters as `$'…'` words.
+fn shell_escape(text: &str) -> String {
+    if !text.chars().any(char::is_control) {
+        return shell_quote(text, false);
+    }
+
+    let mut quoted = String::with_capacity(text.len() + 8);
+    let mut plain = String::new();
+    let mut controls = String::new();
+    for character in text.chars() {
+        if character.is_control() {
+            if !plain.is_empty() {
+                quoted.push_str(&shell_quote(&plain, true));
+                plain.clear();
+            }
+            push_c_escape(&mut controls, character);
+        } else {
+            if !controls.is_empty() {
+                quoted.push_str(&format!("$'{controls}'"));
+                controls.clear();
+            }
+            plain.push(character);
+        }
+    }
+    if !plain.is_empty() {
+        quoted.push_str(&shell_quote(&plain, true));
+    }
+    if !controls.is_empty() {
+        quoted.push_str(&format!("$'{controls}'"));
+    }
+    quoted
+}
+
+/// Apply C escapes, also escaping `extra` when given.
+fn c_escape(text: &str, extra: Option<char>) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if character == '\\' || Some(character) == extra {
+            escaped.push('\\');
+            escaped.push(character);
+        } else if character.is_control() {
+            push_c_escape(&mut escaped, character);
+        } else {
+            escaped.push(character);
+        }
+    }
+    escaped
+}
+
+fn push_c_escape(escaped: &mut String, character: char) {
+    match character {
+        '\x07' => escaped.push_str("\\a"),
+        '\x08' => escaped.push_str("\\b"),
+        '\x0c' => escaped.push_str("\\f"),
+        '\n' => escaped.push_str("\\n"),
+        '\r' => escaped.push_str("\\r"),
+        '\t' => escaped.push_str("\\t"),
+        '\x0b' => escaped.push_str("\\v"),
+        _ => {
+            let mut buffer = [0; 4];
+            for byte in character.encode_utf8(&mut buffer).bytes() {
+                escaped.push_str(&format!("\\{byte:03o}"));
+            }
+        }
+    }
+}
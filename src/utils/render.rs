@@ -5,8 +5,10 @@
 
 use chrono::{DateTime, Local};
 use colored_text::{ColorLevel, Colorize, StyledText};
+use std::env;
 use std::fmt::Write as FmtWrite;
 use std::io::{self, IsTerminal, Write as IoWrite};
+use std::ops::Range;
 use std::time::{Duration, SystemTime};
 
 use strip_ansi_escapes::strip_str;
@@ -15,16 +17,35 @@ use terminal_size::{Height, Width, terminal_size};
 
 use crate::Params;
 use crate::platform::{self, LongColumn, LongFormatLayoutOptions};
-use crate::structs::{AttributeDisplay, FileInfo, NameStyle, ShortFormat};
+use crate::structs::{
+    AttributeDisplay, FileInfo, NameStyle, ShortFormat, SyntheticEntry,
+    TreeStyle,
+};
 use crate::utils;
 use crate::utils::color::long_format_color_level;
-use crate::utils::file::check_display_name;
-use crate::utils::table::{Cell, HeaderCell, HeaderRow, Row, Table};
+use crate::utils::dired::{DIRED_INDENT, leading_escape_len};
+use crate::utils::file::{check_display_name, entry_name, mount_point_marker};
+use crate::utils::format::SizeScale;
+use crate::utils::hyperlink::{
+    LINK_END, link_name, link_start, links_enabled,
+};
+use crate::utils::icons::Icon;
+use crate::utils::quoting::quote_name;
+use crate::utils::table::{
+    Cell, HeaderCell, HeaderRow, MarkedWrite, Row, StreamingTable, Table,
+    visible_width,
+};
 use crate::utils::time::{DAY, MONTH, WEEK, YEAR};
+use crate::utils::walk::WalkSummary;
 
 const SHORT_COLUMN_GAP: usize = 2;
+/// Grid width used for `-w 0`, leaving room for the grid's own gap sums.
+const UNLIMITED_WIDTH: usize = usize::MAX / 2;
+/// Environment variable that sets the short-format width off a terminal.
+const COLUMNS_ENV_VAR: &str = "COLUMNS";
 const LONG_TABLE_DEFAULT_GAP: usize = 2;
 const LONG_TABLE_RELATED_GAP: usize = 1;
+const USAGE_BAR_WIDTH: usize = 20;
 const LARGE_SIZE_BYTES: u64 = 1024 * 1024;
 const HUGE_SIZE_BYTES: u64 = 1024 * 1024 * 1024;
 const HEADER_SALMON_TRUECOLOR: (u8, u8, u8) = (250, 128, 114);
@@ -65,19 +86,143 @@ pub fn display_long_format(
     print_table(&build_long_format_table(file_info, params))
 }
 
+/// Render long-format rows to `output`, marking each entry name.
+pub(crate) fn write_long_format(
+    output: &mut impl MarkedWrite,
+    file_info: &[FileInfo],
+    params: &Params,
+) -> io::Result<()> {
+    build_long_format_table(file_info, params).write_marked_to(output)
+}
+
 /// Style a directory section header.
 pub(crate) fn directory_header_text(header: &str) -> String {
     header.blue().bold().to_string()
 }
 
-/// Render long-format rows with prefixes prepended to the name column.
-pub(crate) fn display_long_format_with_name_prefixes<'a>(
-    file_info: impl IntoIterator<Item = (&'a FileInfo, &'a str)>,
+/// Describe traversal totals the way `tree` ends its output.
+///
+/// Pruned directories are included in the directory count and also shown on
+/// their own; symlinks and errors only appear when there are some.
+pub(crate) fn summary_text(summary: &WalkSummary, params: &Params) -> String {
+    let mut text = plural(summary.directories, "directory", "directories");
+    if summary.pruned > 0 {
+        let _ = write!(text, " ({} pruned)", summary.pruned);
+    }
+    let _ = write!(text, ", {}", plural(summary.files, "file", "files"));
+    if summary.symlinks > 0 {
+        let symlinks = plural(summary.symlinks, "symlink", "symlinks");
+        let _ = write!(text, ", {symlinks}");
+    }
+    let (size, unit) =
+        utils::format::show_size(summary.bytes, params.size_scale());
+    if unit.is_empty() {
+        let _ = write!(text, ", {}", plural(summary.bytes, "byte", "bytes"));
+    } else {
+        let _ = write!(text, ", {size}{unit}");
+    }
+    if summary.errors > 0 {
+        let _ =
+            write!(text, ", {}", plural(summary.errors, "error", "errors"));
+    }
+
+    text
+}
+
+fn plural<T>(count: T, singular: &str, plural: &str) -> String
+where
+    T: std::fmt::Display + PartialEq + From<u8>,
+{
+    if count == T::from(1) {
+        format!("{count} {singular}")
+    } else {
+        format!("{count} {plural}")
+    }
+}
+
+/// One row of `--usage` output.
+#[derive(Debug)]
+pub(crate) struct UsageRow {
+    pub(crate) name: UsageName,
+    pub(crate) name_prefix: String,
+    pub(crate) size: u64,
+    /// Fraction of the parent directory's size, from `0.0` to `1.0`.
+    pub(crate) share: f64,
+}
+
+/// What a `--usage` row describes.
+#[derive(Debug)]
+pub(crate) enum UsageName {
+    Entry(Box<FileInfo>),
+    /// Entries too small to list on their own, folded into one row.
+    Others(usize),
+}
+
+/// Render `--usage` rows with sizes, percentages, and proportional bars.
+pub(crate) fn display_usage_rows(
+    rows: &[UsageRow],
     params: &Params,
 ) -> io::Result<()> {
-    print_table(&build_long_format_table_with_name_prefixes(
-        file_info, params,
-    ))
+    print_table(&build_usage_table(rows, params))
+}
+
+/// Build the `--usage` table without printing it.
+pub(crate) fn build_usage_table(rows: &[UsageRow], params: &Params) -> Table {
+    let mut table = Table::new();
+    table.set_line_end(line_end(params));
+    table.set_default_gap(LONG_TABLE_DEFAULT_GAP);
+    table.set_column_gap(0, LONG_TABLE_RELATED_GAP);
+    let color_level = long_format_color_level(params);
+    // Usage output is about magnitudes, so sizes are always scaled.
+    let scale = params.size_scale().unwrap_or(SizeScale::Binary);
+
+    for row in rows {
+        let (display_size, unit) =
+            utils::format::show_size(row.size, Some(scale));
+        let mut cells = vec![
+            size_cell(&display_size, row.size, params, color_level, true),
+            size_cell(unit, row.size, params, color_level, false),
+            Cell::right(format!("{:.1}%", row.share * 100.0)),
+            Cell::new(usage_bar(row.share, params.tree_style)),
+        ];
+        if !params.no_icons {
+            cells.push(icon_cell(match &row.name {
+                UsageName::Entry(info) => info.item_icon.as_ref(),
+                UsageName::Others(_) => None,
+            }));
+        }
+        let name = match &row.name {
+            UsageName::Entry(info) => {
+                link_entry(&check_display_name(info), info, params)
+            }
+            UsageName::Others(count) => {
+                let text = format!("{count} other entries");
+                if color_level == ColorLevel::NoColor {
+                    text
+                } else {
+                    text.dim().to_string()
+                }
+            }
+        };
+        cells.push(Cell::new(format!("{}{name}", row.name_prefix)));
+        table.add_row(Row::new(cells));
+    }
+
+    table
+}
+
+/// Draw a fixed-width bar filled in proportion to `share`.
+pub(crate) fn usage_bar(share: f64, style: TreeStyle) -> String {
+    let (filled, empty) = match style {
+        TreeStyle::Ascii => ('#', '.'),
+        _ => ('█', '░'),
+    };
+    let width = (share.clamp(0.0, 1.0) * USAGE_BAR_WIDTH as f64).round();
+    let width = width as usize;
+
+    std::iter::repeat_n(filled, width)
+        .chain(std::iter::repeat_n(empty, USAGE_BAR_WIDTH - width))
+        .collect()
 }
 
 /// Build the long-format table without printing it.
@@ -95,20 +240,15 @@ pub(crate) fn build_long_format_table_with_name_prefixes<'a>(
     file_info: impl IntoIterator<Item = (&'a FileInfo, &'a str)>,
     params: &Params,
 ) -> Table {
-    let mut table = Table::new();
-    table.set_default_gap(LONG_TABLE_DEFAULT_GAP);
     let color_level = long_format_color_level(params);
     let entries: Vec<_> = file_info.into_iter().collect();
     let columns = long_format_columns(params);
-    apply_long_format_gaps(&mut table, &columns);
-
-    if params.header && !entries.is_empty() {
-        table.set_header(long_format_header_row(
-            &columns,
-            params.attributes,
-            color_level,
-        ));
-    }
+    let mut table = long_format_layout_table(
+        &columns,
+        params,
+        color_level,
+        !entries.is_empty(),
+    );
 
     for (info, name_prefix) in entries {
         table.add_row(long_format_row(
@@ -123,11 +263,104 @@ pub(crate) fn build_long_format_table_with_name_prefixes<'a>(
     table
 }
 
+/// Widest scaled size, `16384.0` pebibytes for `u64::MAX`.
+const STREAM_SIZE_WIDTH: usize = 7;
+
+/// Long-format rows written to `output` as they are produced.
+///
+/// Used for trees, which can be too large to collect before printing; see
+/// [`StreamingTable`] for how column widths are chosen.
+pub(crate) struct LongFormatStream<'a, W: IoWrite> {
+    table: StreamingTable<W>,
+    params: &'a Params,
+    color_level: ColorLevel,
+    columns: Vec<LongColumn>,
+}
+
+impl<'a, W: IoWrite> LongFormatStream<'a, W> {
+    pub(crate) fn new(params: &'a Params, output: W) -> Self {
+        let color_level = long_format_color_level(params);
+        let columns = long_format_columns(params);
+        let layout =
+            long_format_layout_table(&columns, params, color_level, true);
+
+        let mut table = StreamingTable::new(layout, output);
+        if params.size_scale().is_some() {
+            for (index, column) in columns.iter().enumerate() {
+                match column {
+                    LongColumn::Size => {
+                        table.set_stream_width(index, STREAM_SIZE_WIDTH);
+                    }
+                    LongColumn::Unit => table.set_stream_width(index, 1),
+                    _ => {}
+                }
+            }
+        }
+
+        Self {
+            table,
+            params,
+            color_level,
+            columns,
+        }
+    }
+
+    pub(crate) fn write_row(
+        &mut self,
+        info: &FileInfo,
+        name_prefix: &str,
+    ) -> io::Result<()> {
+        self.table.add_row(long_format_row(
+            info,
+            name_prefix,
+            self.params,
+            self.color_level,
+            &self.columns,
+        ))
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.table.flush()
+    }
+
+    pub(crate) fn finish(self) -> io::Result<W> {
+        self.table.finish()
+    }
+}
+
+/// Create an empty long-format table with its gaps and optional header.
+fn long_format_layout_table(
+    columns: &[LongColumn],
+    params: &Params,
+    color_level: ColorLevel,
+    header: bool,
+) -> Table {
+    let mut table = Table::new();
+    if params.dired {
+        table.set_indent(DIRED_INDENT);
+    }
+    table.set_line_end(line_end(params));
+    table.set_default_gap(LONG_TABLE_DEFAULT_GAP);
+    apply_long_format_gaps(&mut table, columns);
+
+    if params.header && header {
+        table.set_header(long_format_header_row(
+            columns,
+            params.attributes,
+            color_level,
+        ));
+    }
+
+    table
+}
+
 fn long_format_columns(params: &Params) -> Vec<LongColumn> {
     platform::long_format_layout(&LongFormatLayoutOptions {
         permission_display: params.permissions,
         include_size_unit: params.size_scale().is_some(),
         include_icon: !params.no_icons,
+        include_owner: !params.no_owner,
+        include_group: !params.no_group,
     })
     .columns
 }
@@ -161,11 +394,24 @@ fn long_format_row(
     let size_scale = params.size_scale();
     let (display_size, units) =
         utils::format::show_size(info.size, size_scale);
-    let display_name = format!("{}{}", name_prefix, check_display_name(info));
+    let virtual_entry = info.synthetic == Some(SyntheticEntry::Virtual);
     let mut cells = Vec::with_capacity(columns.len());
 
     for column in columns {
         cells.push(match column {
+            LongColumn::Icon => icon_cell(info.item_icon.as_ref()),
+            LongColumn::Name => name_cell(info, name_prefix, params),
+            // Path-list entries that do not exist have no metadata to show.
+            LongColumn::Unit if virtual_entry => Cell::new(""),
+            _ if virtual_entry => Cell::new("?"),
+            LongColumn::Size => {
+                size_cell(&display_size, info.size, params, color_level, true)
+            }
+            LongColumn::Unit => {
+                size_cell(units, info.size, params, color_level, false)
+            }
+            // Elision rows only carry the size of the entries they stand for.
+            _ if info.synthetic.is_some() => Cell::new(""),
             LongColumn::UnixSymbolicPermissions => {
                 symbolic_permission_cell(info, params, color_level)
             }
@@ -182,26 +428,88 @@ fn long_format_row(
             LongColumn::Links => Cell::new(info.nlink.to_string()),
             LongColumn::User => Cell::new(info.user.cyan().to_string()),
             LongColumn::Group => Cell::new(info.group.green().to_string()),
-            LongColumn::Size => {
-                size_cell(&display_size, info.size, params, color_level, true)
-            }
-            LongColumn::Unit => {
-                size_cell(units, info.size, params, color_level, false)
-            }
             LongColumn::Date => Cell::right(long_time_text(
                 &display_time,
                 info.mtime,
                 params,
                 color_level,
             )),
-            LongColumn::Icon => icon_cell(info),
-            LongColumn::Name => Cell::new(&display_name),
         });
     }
 
     Row::new(cells)
 }
 
+/// Build the name cell, linking the name and marking it for `--dired`.
+///
+/// Like `ls`, only the quoted name is linked and marked, leaving out type
+/// indicators and link targets.
+fn name_cell(info: &FileInfo, name_prefix: &str, params: &Params) -> Cell {
+    let display_name = check_display_name(info);
+    let linked = links_enabled(params) && info.synthetic.is_none();
+    let marked = params.dired && info.synthetic.is_none();
+    if !linked && !marked {
+        return Cell::new(format!("{name_prefix}{display_name}"));
+    }
+
+    let name = quote_name(&entry_name(&info.full_path), params);
+    // `.`, `..`, and collapsed chains are shown as written instead.
+    let name_len = if info.short_name.starts_with(&name) {
+        name.len()
+    } else {
+        info.short_name.len()
+    };
+    let span = styled_span(&display_name, 0..name_len);
+    let (start, end) = if linked {
+        (link_start(&info.full_path), LINK_END)
+    } else {
+        (String::new(), "")
+    };
+    let mark_start = name_prefix.len() + span.start + start.len();
+    let cell = Cell::new(format!(
+        "{name_prefix}{}{start}{}{end}{}",
+        &display_name[..span.start],
+        &display_name[span.clone()],
+        &display_name[span.end..],
+    ));
+
+    if marked {
+        cell.marked(mark_start..mark_start + span.len())
+    } else {
+        cell
+    }
+}
+
+/// Map a byte range of `text` without its escape sequences back onto `text`.
+///
+/// The range starts after any styling before its first character and ends
+/// right after its last character, so escapes are never split.
+fn styled_span(text: &str, plain: Range<usize>) -> Range<usize> {
+    let mut raw = 0;
+    let mut plain_index = 0;
+    let mut start = None;
+    loop {
+        if start.is_some() && plain_index >= plain.end {
+            break;
+        }
+        let escape = leading_escape_len(&text[raw..]);
+        if escape > 0 {
+            raw += escape;
+            continue;
+        }
+        let Some(character) = text[raw..].chars().next() else {
+            break;
+        };
+        if plain_index == plain.start {
+            start = Some(raw);
+        }
+        raw += character.len_utf8();
+        plain_index += character.len_utf8();
+    }
+
+    start.unwrap_or(raw)..raw
+}
+
 fn long_format_header_row(
     columns: &[LongColumn],
     attributes: AttributeDisplay,
@@ -290,10 +598,8 @@ fn octal_permission_cell(
     Cell::new(long_octal_permission_text(info, params, color_level))
 }
 
-fn icon_cell(info: &FileInfo) -> Cell {
-    info.item_icon
-        .as_ref()
-        .map_or_else(|| Cell::new(""), |icon| Cell::new(icon.to_string()))
+fn icon_cell(icon: Option<&Icon>) -> Cell {
+    icon.map_or_else(|| Cell::new(""), |icon| Cell::new(icon.to_string()))
 }
 
 fn long_file_type_text(
@@ -565,17 +871,18 @@ pub(crate) fn display_short_format(
     file_info: &[FileInfo],
     params: &Params,
 ) -> io::Result<()> {
-    if let Some(short_format) =
-        resolve_short_format(io::stdout().is_terminal(), params.short_format)
+    match resolve_short_format(io::stdout().is_terminal(), params.short_format)
     {
-        let terminal_width = terminal_width_or_default(terminal_size());
-        print_short_grid(&render_short_format(
+        ShortFormat::SingleColumn => print_short_lines(
+            &render_short_single_column_lines(file_info, params),
+            params,
+        ),
+        short_format => print_short_grid(&render_short_format(
             file_info,
-            terminal_width,
+            short_format_width(params),
             short_format,
-        ))
-    } else {
-        print_short_lines(&render_short_single_column_lines(file_info))
+            params,
+        )),
     }
 }
 
@@ -586,62 +893,182 @@ pub(crate) fn render_short_format_lines(
     terminal_width: usize,
     short_format: ShortFormat,
 ) -> Vec<String> {
-    render_short_format(file_info, terminal_width, short_format)
-        .lines()
-        .map(str::to_owned)
-        .collect()
+    render_short_format(
+        file_info,
+        terminal_width,
+        short_format,
+        &Params::default(),
+    )
+    .lines()
+    .map(str::to_owned)
+    .collect()
 }
 
-fn render_short_format(
+pub(crate) fn render_short_format(
     file_info: &[FileInfo],
     terminal_width: usize,
     short_format: ShortFormat,
+    params: &Params,
 ) -> String {
-    Grid::new(
-        short_render_cells(file_info),
+    let direction = match short_format {
+        ShortFormat::Vertical => Direction::TopToBottom,
+        ShortFormat::Across => Direction::LeftToRight,
+        ShortFormat::Commas => {
+            return render_comma_separated(file_info, terminal_width, params);
+        }
+        ShortFormat::SingleColumn => {
+            return render_short_single_column_lines(file_info, params)
+                .into_iter()
+                .map(|line| format!("{line}{}", line_end(params)))
+                .collect();
+        }
+    };
+
+    let grid = Grid::new(
+        short_render_cells(file_info, params),
         GridOptions {
-            direction: match short_format {
-                ShortFormat::Vertical => Direction::TopToBottom,
-                ShortFormat::Across => Direction::LeftToRight,
-            },
-            filling: Filling::Spaces(SHORT_COLUMN_GAP),
+            direction,
+            filling: short_grid_filling(params),
             width: terminal_width,
         },
     )
-    .to_string()
+    .to_string();
+    if params.zero {
+        // Names are literal under `--zero`, so one holding a newline splits
+        // its grid row here too; only single-column output keeps it intact.
+        grid.replace('\n', "\0")
+    } else {
+        grid
+    }
+}
+
+/// Return how grid columns are padded; `-T` only changes the padding, never
+/// the cells or the layout.
+fn short_grid_filling(params: &Params) -> Filling {
+    match params.tabsize {
+        Some(tab_size) if tab_size > 0 => Filling::Tabs {
+            spaces: SHORT_COLUMN_GAP,
+            tab_size,
+        },
+        _ => Filling::Spaces(SHORT_COLUMN_GAP),
+    }
+}
+
+/// Render entries separated by `, `, like GNU `ls -m`.
+///
+/// Entries are the bare decorated names, with an icon only when icons are
+/// shown and never padded to a column. A line breaks after the comma when
+/// the next entry would reach the terminal width.
+fn render_comma_separated(
+    file_info: &[FileInfo],
+    terminal_width: usize,
+    params: &Params,
+) -> String {
+    let mut output = String::new();
+    let mut position = 0;
+    for (index, info) in file_info.iter().enumerate() {
+        let name = short_render_cell(info, params);
+        let width = visible_width(&name);
+        if index > 0 {
+            if position + width + 2 < terminal_width {
+                output.push_str(", ");
+                position += 2;
+            } else {
+                output.push(',');
+                output.push(line_end(params));
+                position = 0;
+            }
+        }
+        output.push_str(&name);
+        position += width;
+    }
+    if !output.is_empty() {
+        output.push(line_end(params));
+    }
+    output
 }
 
 /// Render one unpadded short-format entry per line.
 pub(crate) fn render_short_single_column_lines(
     file_info: &[FileInfo],
+    params: &Params,
 ) -> Vec<String> {
-    short_render_cells(file_info)
+    short_render_cells(file_info, params)
 }
 
-/// Resolve the short grid format for this stdout context.
+/// Return the character that ends each output line: NUL under `--zero`.
+pub(crate) fn line_end(params: &Params) -> char {
+    if params.zero { '\0' } else { '\n' }
+}
+
+/// Resolve the short format for this stdout context.
+///
+/// Terminals default to vertical columns; redirected output prints one entry
+/// per line.
 pub(crate) fn resolve_short_format(
     is_terminal: bool,
     short_format: Option<ShortFormat>,
-) -> Option<ShortFormat> {
-    short_format.or(is_terminal.then_some(ShortFormat::Vertical))
+) -> ShortFormat {
+    short_format.unwrap_or(if is_terminal {
+        ShortFormat::Vertical
+    } else {
+        ShortFormat::SingleColumn
+    })
+}
+
+fn short_render_cells(file_info: &[FileInfo], params: &Params) -> Vec<String> {
+    file_info
+        .iter()
+        .map(|info| short_render_cell(info, params))
+        .collect()
 }
 
-fn short_render_cells(file_info: &[FileInfo]) -> Vec<String> {
-    file_info.iter().map(short_render_cell).collect()
+/// Return the width short-format layouts fill.
+///
+/// `-w` wins over the terminal, which wins over `COLUMNS`; a width of 0 lets
+/// rows grow without limit.
+pub(crate) fn short_format_width(params: &Params) -> usize {
+    let width = params.width.unwrap_or_else(|| {
+        terminal_width_or_default(
+            terminal_size(),
+            env::var(COLUMNS_ENV_VAR).ok().as_deref(),
+        )
+    });
+    if width == 0 { UNLIMITED_WIDTH } else { width }
 }
 
-/// Return the detected terminal width, or the standard 80-column fallback.
+/// Return the detected terminal width, then a valid `COLUMNS` value, or the
+/// standard 80-column fallback.
 pub(crate) fn terminal_width_or_default(
     size: Option<(Width, Height)>,
+    columns: Option<&str>,
 ) -> usize {
     size.map(|(Width(width), _)| usize::from(width))
+        .or_else(|| columns.and_then(|columns| columns.parse().ok()))
         .unwrap_or(80)
 }
 
-fn short_render_cell(info: &FileInfo) -> String {
+fn short_render_cell(info: &FileInfo, params: &Params) -> String {
     let display_name = check_display_name(info);
     let (prefix, name) = short_cell_parts(info, &display_name);
-    format!("{prefix}{}", style_short_segment(info, name))
+    let marker = if info.mount_point {
+        mount_point_marker(info.dimmed)
+    } else {
+        String::new()
+    };
+    format!(
+        "{prefix}{}{marker}",
+        link_entry(&style_short_segment(info, name), info, params)
+    )
+}
+
+/// Link a rendered name to its file, leaving synthetic rows unlinked.
+fn link_entry(text: &str, info: &FileInfo, params: &Params) -> String {
+    if info.synthetic.is_some() {
+        text.to_string()
+    } else {
+        link_name(text, &info.full_path, params)
+    }
 }
 
 fn short_cell_parts(info: &FileInfo, display_name: &str) -> (String, String) {
@@ -685,10 +1112,10 @@ fn print_table(table: &Table) -> io::Result<()> {
     stdout.flush()
 }
 
-fn print_short_lines(lines: &[String]) -> io::Result<()> {
+fn print_short_lines(lines: &[String], params: &Params) -> io::Result<()> {
     let mut stdout = io::stdout();
     for line in lines {
-        writeln!(stdout, "{line}")?;
+        write!(stdout, "{line}{}", line_end(params))?;
     }
     stdout.flush()
 }
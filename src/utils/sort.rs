@@ -1,24 +1,38 @@
 //! Directory-entry sorting helpers.
 
 use std::cmp::Ordering;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crate::platform;
-use crate::structs::{Params, SortMode};
-use crate::utils::file::DirectoryEntryData;
+use crate::structs::{Params, SortMode, TotalSize};
+use crate::utils::disk_usage::{self, UsageCache};
+use crate::utils::file::{DirectoryEntryData, is_dot_entry};
 
 /// Sort visible directory entries using the resolved runtime policy.
 pub(crate) fn sort_entries(
     entries: &mut [DirectoryEntryData],
     params: &Params,
+    usage: &UsageCache,
 ) {
     if params.sort == SortMode::None {
         return;
     }
 
+    // Directory totals are expensive, so measure each entry once up front.
+    let total_sizes = params
+        .total_size
+        .filter(|_| params.sort == SortMode::Size)
+        .map(|total_size| total_sizes(entries, total_size, params, usage));
+
     entries.sort_by(|left, right| {
-        let ordering = mode_ordering(left, right, params.sort)
-            .then_with(|| name_ordering(left, right));
+        let ordering = match &total_sizes {
+            Some(sizes) => {
+                sizes[&left.path].cmp(&sizes[&right.path]).reverse()
+            }
+            None => mode_ordering(left, right, params.sort),
+        }
+        .then_with(|| name_ordering(left, right));
 
         if params.reverse {
             ordering.reverse()
@@ -28,6 +42,33 @@ pub(crate) fn sort_entries(
     });
 }
 
+fn total_sizes(
+    entries: &[DirectoryEntryData],
+    total_size: TotalSize,
+    params: &Params,
+    usage: &UsageCache,
+) -> HashMap<PathBuf, u64> {
+    entries
+        .iter()
+        .map(|entry| {
+            let size = entry.metadata.as_ref().map_or(0, |metadata| {
+                if entry.file_name.to_str().is_some_and(is_dot_entry) {
+                    metadata.len()
+                } else {
+                    disk_usage::entry_size(
+                        &entry.path,
+                        metadata,
+                        total_size,
+                        params,
+                        usage,
+                    )
+                }
+            });
+            (entry.path.clone(), size)
+        })
+        .collect()
+}
+
 fn mode_ordering(
     left: &DirectoryEntryData,
     right: &DirectoryEntryData,
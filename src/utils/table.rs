@@ -2,11 +2,14 @@
 
 use std::fmt;
 use std::io::{self, Write};
+use std::ops::Range;
 
 use strip_ansi_escapes::strip_str;
 use unicode_width::UnicodeWidthStr;
 
 const SPACE_BUFFER: [u8; 64] = [b' '; 64];
+/// Rows a [`StreamingTable`] buffers to pre-scan its column widths.
+const STREAM_PRESCAN_ROWS: usize = 256;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum Alignment {
@@ -19,6 +22,31 @@ pub(crate) struct Cell {
     text: String,
     width: usize,
     align: Alignment,
+    /// Bytes of `text` reported to [`MarkedWrite`] output when written.
+    mark: Option<Range<usize>>,
+}
+
+/// Output that records where marked cell text lands.
+pub(crate) trait MarkedWrite: Write {
+    /// Note that `span` of the next bytes written is marked.
+    fn mark(&mut self, span: Range<usize>);
+}
+
+/// Output that ignores cell marks.
+struct Unmarked<W>(W);
+
+impl<W: Write> Write for Unmarked<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: Write> MarkedWrite for Unmarked<W> {
+    fn mark(&mut self, _span: Range<usize>) {}
 }
 
 impl Cell {
@@ -30,15 +58,27 @@ impl Cell {
         Self::with_alignment(text, Alignment::Right)
     }
 
+    /// Mark the byte range `span` of this cell's text, such as a file name
+    /// inside its styling.
+    pub(crate) fn marked(mut self, span: Range<usize>) -> Self {
+        self.mark = Some(span);
+        self
+    }
+
     fn with_alignment(text: impl Into<String>, align: Alignment) -> Self {
         let text = text.into();
         let width = visible_width(&text);
-        Self { text, width, align }
+        Self {
+            text,
+            width,
+            align,
+            mark: None,
+        }
     }
 
     fn write_padded(
         &self,
-        output: &mut impl Write,
+        output: &mut impl MarkedWrite,
         target_width: usize,
         skip_right_fill: bool,
     ) -> io::Result<()> {
@@ -46,19 +86,26 @@ impl Cell {
 
         match self.align {
             Alignment::Left => {
-                write!(output, "{}", self.text)?;
+                self.write_text(output)?;
                 if !skip_right_fill {
                     write_spaces(output, padding)?;
                 }
             }
             Alignment::Right => {
                 write_spaces(output, padding)?;
-                write!(output, "{}", self.text)?;
+                self.write_text(output)?;
             }
         }
 
         Ok(())
     }
+
+    fn write_text(&self, output: &mut impl MarkedWrite) -> io::Result<()> {
+        if let Some(span) = &self.mark {
+            output.mark(span.clone());
+        }
+        output.write_all(self.text.as_bytes())
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -116,6 +163,10 @@ impl HeaderRow {
 pub(crate) struct Table {
     header: Option<HeaderRow>,
     rows: Vec<Row>,
+    /// Spaces written before every line.
+    indent: usize,
+    /// Character written after every line.
+    line_end: char,
     default_gap: usize,
     column_gaps: Vec<usize>,
 }
@@ -145,6 +196,8 @@ impl Table {
         Self {
             header: None,
             rows: Vec::new(),
+            indent: 1,
+            line_end: '\n',
             default_gap: 1,
             column_gaps: Vec::new(),
         }
@@ -158,6 +211,14 @@ impl Table {
         self.rows.push(row);
     }
 
+    pub(crate) fn set_indent(&mut self, indent: usize) {
+        self.indent = indent;
+    }
+
+    pub(crate) fn set_line_end(&mut self, line_end: char) {
+        self.line_end = line_end;
+    }
+
     pub(crate) fn set_default_gap(&mut self, gap: usize) {
         self.default_gap = gap;
     }
@@ -170,6 +231,14 @@ impl Table {
     }
 
     pub(crate) fn write_to(&self, output: &mut impl Write) -> io::Result<()> {
+        self.write_marked_to(&mut Unmarked(output))
+    }
+
+    /// Write the table, reporting marked cell text to `output`.
+    pub(crate) fn write_marked_to(
+        &self,
+        output: &mut impl MarkedWrite,
+    ) -> io::Result<()> {
         let widths = self.column_widths();
         if let Some(header) = &self.header {
             self.write_header(output, header, &widths)?;
@@ -184,11 +253,11 @@ impl Table {
 
     fn write_header(
         &self,
-        output: &mut impl Write,
+        output: &mut impl MarkedWrite,
         header: &HeaderRow,
         widths: &[usize],
     ) -> io::Result<()> {
-        write!(output, " ")?;
+        write_spaces(output, self.indent)?;
 
         let mut column = 0;
         for header_cell in &header.cells {
@@ -221,30 +290,40 @@ impl Table {
             column += 1;
         }
 
-        writeln!(output)?;
+        write!(output, "{}", self.line_end)?;
         Ok(())
     }
 
     fn write_row(
         &self,
-        output: &mut impl Write,
+        output: &mut impl MarkedWrite,
         row: &Row,
         widths: &[usize],
     ) -> io::Result<()> {
-        write!(output, " ")?;
+        write_spaces(output, self.indent)?;
+        // A cell wider than its column is written whole; the excess is taken
+        // back from the padding and gaps that follow so later columns return
+        // to their settled positions as soon as there is room.
+        let mut overflow = 0;
         for column in 0..widths.len() {
             let skip_right_fill = column == widths.len() - 1;
+            let width = widths[column].saturating_sub(overflow);
+            overflow = overflow.saturating_sub(widths[column]);
             if let Some(cell) = row.cells.get(column) {
-                cell.write_padded(output, widths[column], skip_right_fill)?;
+                overflow += cell.width.saturating_sub(width);
+                cell.write_padded(output, width, skip_right_fill)?;
             } else if !skip_right_fill {
-                write_spaces(output, widths[column])?;
+                write_spaces(output, width)?;
             }
 
             if !skip_right_fill {
-                write_spaces(output, self.gap_after(column))?;
+                let gap = self.gap_after(column);
+                let kept = gap.saturating_sub(overflow).max(gap.min(1));
+                overflow -= gap - kept;
+                write_spaces(output, kept)?;
             }
         }
-        writeln!(output)?;
+        write!(output, "{}", self.line_end)?;
         Ok(())
     }
 
@@ -321,6 +400,93 @@ impl Table {
     }
 }
 
+/// Table writer that emits rows while they are still being produced.
+///
+/// The first rows are buffered so column widths can be pre-scanned from them.
+/// Once those widths are settled they never change, since rows already
+/// written cannot be realigned. Columns with a known worst case can be given
+/// a minimum streaming width so later rows still fit; any other cell that
+/// outgrows its column pushes only the rest of its own row to the right.
+/// A table that never receives a row writes nothing, not even its header.
+pub(crate) struct StreamingTable<W: Write> {
+    /// Header, gaps, and the rows buffered until widths are settled.
+    layout: Table,
+    /// Minimum column widths applied when rows outlast the pre-scan.
+    stream_widths: Vec<usize>,
+    widths: Option<Vec<usize>>,
+    output: W,
+}
+
+impl<W: Write> StreamingTable<W> {
+    pub(crate) fn new(layout: Table, output: W) -> Self {
+        Self {
+            layout,
+            stream_widths: Vec::new(),
+            widths: None,
+            output,
+        }
+    }
+
+    /// Reserve at least `width` for `column` once rows are streamed.
+    ///
+    /// Tables that fit in the pre-scan keep their tight widths.
+    pub(crate) fn set_stream_width(&mut self, column: usize, width: usize) {
+        if self.stream_widths.len() <= column {
+            self.stream_widths.resize(column + 1, 0);
+        }
+        self.stream_widths[column] = width;
+    }
+
+    pub(crate) fn add_row(&mut self, row: Row) -> io::Result<()> {
+        let Some(widths) = &self.widths else {
+            self.layout.add_row(row);
+            if self.layout.rows.len() >= STREAM_PRESCAN_ROWS {
+                self.settle_widths(true)?;
+            }
+            return Ok(());
+        };
+
+        self.layout
+            .write_row(&mut Unmarked(&mut self.output), &row, widths)
+    }
+
+    /// Flush rows written so far; buffered pre-scan rows stay buffered.
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    /// Write any rows still buffered and flush the output.
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        if self.widths.is_none() && !self.layout.rows.is_empty() {
+            self.settle_widths(false)?;
+        }
+        self.output.flush()?;
+        Ok(self.output)
+    }
+
+    fn settle_widths(&mut self, streaming: bool) -> io::Result<()> {
+        let mut widths = self.layout.column_widths();
+        if streaming {
+            for (width, minimum) in widths.iter_mut().zip(&self.stream_widths)
+            {
+                *width = (*width).max(*minimum);
+            }
+        }
+
+        let mut output = Unmarked(&mut self.output);
+        if let Some(header) = &self.layout.header {
+            self.layout.write_header(&mut output, header, &widths)?;
+        }
+        for row in &self.layout.rows {
+            self.layout.write_row(&mut output, row, &widths)?;
+        }
+
+        self.widths = Some(widths);
+        self.layout.rows.clear();
+        Ok(())
+    }
+}
+
 impl Default for Table {
     fn default() -> Self {
         Self::new()
@@ -353,8 +519,36 @@ fn write_spaces(output: &mut impl Write, count: usize) -> io::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Cell, HeaderCell, HeaderRow, Row, Table};
-    use std::io::{self, Cursor};
+    use super::{
+        Cell, HeaderCell, HeaderRow, MarkedWrite, Row, STREAM_PRESCAN_ROWS,
+        StreamingTable, Table, Unmarked,
+    };
+    use std::io::{self, Cursor, Write};
+    use std::ops::Range;
+
+    /// Buffer that records marks at their absolute offsets.
+    #[derive(Default)]
+    struct MarkRecorder {
+        output: Vec<u8>,
+        marks: Vec<Range<usize>>,
+    }
+
+    impl Write for MarkRecorder {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl MarkedWrite for MarkRecorder {
+        fn mark(&mut self, span: Range<usize>) {
+            let position = self.output.len();
+            self.marks.push(position + span.start..position + span.end);
+        }
+    }
 
     #[test]
     fn table_header_contributes_to_widths_and_uses_column_gaps() {
@@ -464,15 +658,153 @@ mod tests {
         assert_eq!(table.to_string(), " a wide end\n b      \n");
     }
 
+    #[test]
+    fn table_reports_marked_cell_text_at_its_written_offset() {
+        let mut table = Table::new();
+        table.set_indent(2);
+        table.add_row(Row::new(vec![
+            Cell::right("1"),
+            Cell::new("<a>").marked(1..2),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::right("10"),
+            Cell::new("<bc>").marked(1..3),
+        ]));
+        let mut output = MarkRecorder::default();
+
+        table.write_marked_to(&mut output).unwrap();
+
+        let rendered = String::from_utf8(output.output).unwrap();
+        assert_eq!(rendered, "   1 <a>\n  10 <bc>\n");
+        let marked = output
+            .marks
+            .into_iter()
+            .map(|span| &rendered[span])
+            .collect::<Vec<_>>();
+        assert_eq!(marked, ["a", "bc"]);
+    }
+
     #[test]
     fn table_propagates_header_cell_write_errors() {
         let table = Table::new();
         let header = HeaderRow::new(vec![HeaderCell::new("Name")]);
         // One byte lets the leading-space write succeed, then the header cell fails.
-        let mut output = Cursor::new([0_u8; 1]);
+        let mut output = Unmarked(Cursor::new([0_u8; 1]));
 
         let err = table.write_header(&mut output, &header, &[4]).unwrap_err();
 
         assert_eq!(err.kind(), io::ErrorKind::WriteZero);
     }
+
+    #[test]
+    fn streaming_table_matches_table_until_widths_settle() {
+        let mut table = Table::new();
+        table.set_header(HeaderRow::new(vec![
+            HeaderCell::right("Size"),
+            HeaderCell::new("Name"),
+        ]));
+        let mut streaming = StreamingTable::new(table.clone(), Vec::new());
+        for (size, name) in [("1", "a"), ("12345", "b")] {
+            let row = Row::new(vec![Cell::right(size), Cell::new(name)]);
+            table.add_row(row.clone());
+            streaming.add_row(row).unwrap();
+        }
+
+        let output = String::from_utf8(streaming.finish().unwrap()).unwrap();
+
+        assert_eq!(output, table.to_string());
+    }
+
+    #[test]
+    fn streaming_table_keeps_widths_after_pre_scan() {
+        let mut streaming = StreamingTable::new(Table::new(), Vec::new());
+        for _ in 0..STREAM_PRESCAN_ROWS + 44 {
+            streaming
+                .add_row(Row::new(vec![
+                    Cell::right("1"),
+                    Cell::new("a"),
+                    Cell::new("x"),
+                ]))
+                .unwrap();
+        }
+        streaming
+            .add_row(Row::new(vec![
+                Cell::right("123456"),
+                Cell::new("bb"),
+                Cell::new("y"),
+            ]))
+            .unwrap();
+
+        let output = String::from_utf8(streaming.finish().unwrap()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), STREAM_PRESCAN_ROWS + 45);
+        assert!(
+            lines[..STREAM_PRESCAN_ROWS + 44]
+                .iter()
+                .all(|l| *l == " 1 a x")
+        );
+        assert_eq!(lines[STREAM_PRESCAN_ROWS + 44], " 123456 bb y");
+    }
+
+    #[test]
+    fn streaming_table_reserves_stream_widths_after_pre_scan() {
+        let mut streaming = StreamingTable::new(Table::new(), Vec::new());
+        streaming.set_stream_width(0, 6);
+        for _ in 0..STREAM_PRESCAN_ROWS + 44 {
+            streaming
+                .add_row(Row::new(vec![Cell::right("1"), Cell::new("a")]))
+                .unwrap();
+        }
+        streaming
+            .add_row(Row::new(vec![Cell::right("123456"), Cell::new("b")]))
+            .unwrap();
+
+        let output = String::from_utf8(streaming.finish().unwrap()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "      1 a");
+        assert_eq!(lines[STREAM_PRESCAN_ROWS + 44], " 123456 b");
+    }
+
+    #[test]
+    fn streaming_table_ignores_stream_widths_within_pre_scan() {
+        let mut streaming = StreamingTable::new(Table::new(), Vec::new());
+        streaming.set_stream_width(0, 6);
+        streaming
+            .add_row(Row::new(vec![Cell::right("1"), Cell::new("a")]))
+            .unwrap();
+
+        let output = String::from_utf8(streaming.finish().unwrap()).unwrap();
+
+        assert_eq!(output, " 1 a\n");
+    }
+
+    #[test]
+    fn table_row_overflow_is_absorbed_by_later_padding() {
+        let mut table = Table::new();
+        table.set_indent(0);
+        let row = Row::new(vec![
+            Cell::new("abcd"),
+            Cell::right("1"),
+            Cell::new("x"),
+        ]);
+        let mut output = Vec::new();
+
+        table
+            .write_row(&mut Unmarked(&mut output), &row, &[2, 4, 1])
+            .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "abcd  1 x\n");
+    }
+
+    #[test]
+    fn streaming_table_without_rows_writes_nothing() {
+        let mut table = Table::new();
+        table.set_header(HeaderRow::new(vec![HeaderCell::new("Name")]));
+
+        let output = StreamingTable::new(table, Vec::new()).finish().unwrap();
+
+        assert!(output.is_empty());
+    }
 }
@@ -0,0 +1,819 @@
+//! Shared directory traversal for recursive and tree output.
+//!
+//! [`Walker`] applies one policy for depth limits, pruning, `.lspignore`
+//! rules, symlink following, filesystem boundaries, and cycle detection, and
+//! reports what it finds as a depth-first stream of [`WalkEvent`]s. Renderers
+//! only decide how to present those events.
+//!
+//! With more than one traversal thread, a worker pool reads and stats the
+//! directories the walker will enter next, a bounded number ahead of time.
+//! The walker still consumes those reads in depth-first order, so output never
+//! depends on scheduling. One [`DirectoryReader`] can be shared by the walks
+//! of every operand so the pool is only started once.
+
+use std::borrow::BorrowMut;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, mpsc};
+use std::thread::{self, JoinHandle};
+
+use crate::Params;
+use crate::platform::{self, FileIdentity};
+use crate::utils::disk_usage::{self, UsageCache};
+use crate::utils::file::{
+    collect_file_names_with_usage, entry_metadata, is_dot_entry,
+};
+use crate::utils::gitignore::LspignoreCache;
+
+/// Traversal choices that differ between recursive and tree output.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct WalkOptions {
+    /// Deepest visible entry level to list; `None` walks without a limit.
+    pub(crate) max_depth: Option<usize>,
+    /// Keep synthetic `.` and `..` entries in the root directory listing.
+    pub(crate) root_dot_entries: bool,
+    /// Merge chains of directories that each hold a single directory into
+    /// one entry at the chain's depth.
+    pub(crate) collapse_chains: bool,
+    /// Most entries to list per directory; the rest are only counted.
+    pub(crate) max_entries: Option<usize>,
+    /// List only directories; other entries are only counted.
+    pub(crate) directories_only: bool,
+}
+
+impl WalkOptions {
+    /// Options for GNU-style recursive sections.
+    pub(crate) fn recursive(params: &Params) -> Self {
+        Self {
+            max_depth: params.recursive_level,
+            root_dot_entries: true,
+            collapse_chains: false,
+            max_entries: params.max_entries,
+            directories_only: false,
+        }
+    }
+
+    /// Options for tree output.
+    pub(crate) fn tree(params: &Params) -> Self {
+        Self {
+            max_depth: Some(params.tree_level),
+            root_dot_entries: false,
+            collapse_chains: params.collapse,
+            max_entries: params.max_entries,
+            directories_only: false,
+        }
+    }
+}
+
+/// One traversal step.
+///
+/// Every entry marked with [`WalkEntry::descend`] is resolved by exactly one
+/// later event: either an [`WalkEvent::Enter`] and its matching
+/// [`WalkEvent::Leave`], or a [`WalkEvent::DirectoryError`].
+#[derive(Debug)]
+pub(crate) enum WalkEvent {
+    /// A directory was read; its descendants follow before the matching
+    /// [`WalkEvent::Leave`].
+    Enter(WalkDirectory),
+    /// Every descendant of the most recently entered directory was visited.
+    Leave,
+    /// A directory could not be listed, or listing it would revisit an
+    /// ancestor.
+    DirectoryError(WalkError),
+    /// An entry inside a directory being entered could not be inspected.
+    EntryError(WalkError),
+}
+
+/// A listed directory and its visible entries in display order.
+#[derive(Debug)]
+pub(crate) struct WalkDirectory {
+    pub(crate) path: PathBuf,
+    /// Visible depth of this directory's entries; operand entries are `1`.
+    pub(crate) depth: usize,
+    pub(crate) entries: Vec<WalkEntry>,
+    /// Entries left out by [`WalkOptions::max_entries`], never descended into.
+    pub(crate) elided: Option<ElidedEntries>,
+    /// Visible entries read from the directory, before any were left out.
+    pub(crate) counts: ChildCounts,
+}
+
+/// Files and subdirectories found in one directory listing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ChildCounts {
+    pub(crate) files: usize,
+    pub(crate) directories: usize,
+}
+
+/// Entries of one directory left out by `--max-entries`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ElidedEntries {
+    pub(crate) count: usize,
+    /// Combined size of the left-out entries as their own rows would show it.
+    pub(crate) bytes: u64,
+}
+
+/// Keep the first `limit` entries and describe the ones left out.
+pub(crate) fn elide_entries(
+    entries: &mut Vec<WalkEntry>,
+    limit: Option<usize>,
+    params: &Params,
+    usage: &UsageCache,
+) -> Option<ElidedEntries> {
+    let limit = limit.filter(|limit| entries.len() > *limit)?;
+    let elided = entries.split_off(limit);
+
+    Some(ElidedEntries {
+        count: elided.len(),
+        bytes: elided
+            .iter()
+            .map(|entry| {
+                params
+                    .total_size
+                    .filter(|_| !is_dot_entry(&entry.name))
+                    .map_or(entry.metadata.len(), |total_size| {
+                        disk_usage::entry_size(
+                            &entry.path,
+                            &entry.metadata,
+                            total_size,
+                            params,
+                            usage,
+                        )
+                    })
+            })
+            .sum(),
+    })
+}
+
+/// A visible directory entry with its traversal decision.
+#[derive(Debug)]
+pub(crate) struct WalkEntry {
+    /// Entry name as listed, including synthetic `.` and `..`.
+    pub(crate) name: String,
+    pub(crate) path: PathBuf,
+    /// Metadata for the link object, or its target when dereferencing.
+    pub(crate) metadata: fs::Metadata,
+    /// Whether the walker will enter this entry.
+    pub(crate) descend: bool,
+    /// Whether this directory was skipped for living on another filesystem.
+    pub(crate) mount_point: bool,
+    /// Whether this directory was skipped by `--prune-dir` or `.lspignore`.
+    pub(crate) pruned: bool,
+    /// Names of single-child directories collapsed into this entry, outermost
+    /// first; `name` and `path` describe the last directory in the chain.
+    pub(crate) chain: Vec<String>,
+}
+
+/// Counts of listed entries for a traversal summary footer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct WalkSummary {
+    pub(crate) directories: usize,
+    pub(crate) files: usize,
+    pub(crate) symlinks: usize,
+    /// Apparent size of every listed non-directory entry.
+    pub(crate) bytes: u64,
+    /// Listed directories that were not descended into because of pruning.
+    pub(crate) pruned: usize,
+    pub(crate) errors: usize,
+    /// Errors that were directory cycles, also counted in `errors`.
+    pub(crate) cycles: usize,
+}
+
+impl WalkSummary {
+    /// Count one listed entry by its metadata.
+    pub(crate) fn count(&mut self, metadata: &fs::Metadata) {
+        if metadata.file_type().is_symlink() {
+            self.symlinks += 1;
+            self.bytes += metadata.len();
+        } else if metadata.is_dir() {
+            self.directories += 1;
+        } else {
+            self.files += 1;
+            self.bytes += metadata.len();
+        }
+    }
+
+    /// Count one entry reported by the walker, skipping `.` and `..`.
+    pub(crate) fn count_entry(&mut self, entry: &WalkEntry) {
+        if matches!(entry.name.as_str(), "." | "..") {
+            return;
+        }
+
+        self.count(&entry.metadata);
+        self.directories += entry.chain.len();
+        self.pruned += usize::from(entry.pruned);
+    }
+}
+
+/// A traversal failure for one path.
+#[derive(Debug)]
+pub(crate) struct WalkError {
+    pub(crate) path: PathBuf,
+    pub(crate) error: io::Error,
+    /// Whether the failure concerns the operand the walk started from.
+    pub(crate) root: bool,
+    /// Whether listing the directory would revisit one already being listed.
+    pub(crate) cycle: bool,
+}
+
+/// Depth-first walker over one directory operand.
+pub(crate) struct Walker<'a, R> {
+    params: &'a Params,
+    options: WalkOptions,
+    reader: R,
+    root: Option<PathBuf>,
+    stack: Vec<Frame>,
+    pending: VecDeque<WalkEvent>,
+}
+
+/// An entered directory whose children are still being visited.
+struct Frame {
+    identity: Option<FileIdentity>,
+    depth: usize,
+    children: std::vec::IntoIter<PathBuf>,
+}
+
+/// Everything read for one directory before the walker decides to enter it.
+struct DirectoryRead {
+    identity: Option<FileIdentity>,
+    listing: io::Result<DirectoryListing>,
+}
+
+struct DirectoryListing {
+    entries: Vec<WalkEntry>,
+    elided: Option<ElidedEntries>,
+    counts: ChildCounts,
+    errors: Vec<WalkError>,
+}
+
+impl<'a, R: BorrowMut<DirectoryReader>> Walker<'a, R> {
+    /// Start a walk at a directory operand.
+    ///
+    /// `reader` may be shared with earlier walks; reads they left behind by
+    /// stopping early are discarded.
+    pub(crate) fn new(
+        root: impl Into<PathBuf>,
+        params: &'a Params,
+        options: WalkOptions,
+        mut reader: R,
+    ) -> Self {
+        reader.borrow_mut().reset();
+        Self {
+            params,
+            options,
+            reader,
+            root: Some(root.into()),
+            stack: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn enter(&mut self, path: PathBuf, depth: usize) {
+        let read = self.reader.borrow_mut().read(
+            &path,
+            depth,
+            self.params,
+            self.options,
+        );
+        let identity = read.identity;
+        if identity.is_some_and(|identity| {
+            self.stack
+                .iter()
+                .any(|frame| frame.identity == Some(identity))
+        }) {
+            self.pending.push_back(WalkEvent::DirectoryError(WalkError {
+                path,
+                error: io::Error::other(
+                    "not listing already-listed directory",
+                ),
+                root: false,
+                cycle: true,
+            }));
+            return;
+        }
+
+        let listing = match read.listing {
+            Ok(listing) => listing,
+            Err(error) => {
+                self.pending.push_back(WalkEvent::DirectoryError(WalkError {
+                    path,
+                    error,
+                    root: depth == 1,
+                    cycle: false,
+                }));
+                return;
+            }
+        };
+
+        let children = listing
+            .entries
+            .iter()
+            .filter(|entry| entry.descend)
+            .map(|entry| entry.path.clone())
+            .collect::<Vec<_>>();
+        self.reader
+            .borrow_mut()
+            .prefetch(&children, depth + 1, self.options);
+        self.pending
+            .extend(listing.errors.into_iter().map(WalkEvent::EntryError));
+        self.stack.push(Frame {
+            identity,
+            depth,
+            children: children.into_iter(),
+        });
+        self.pending.push_back(WalkEvent::Enter(WalkDirectory {
+            path,
+            depth,
+            entries: listing.entries,
+            elided: listing.elided,
+            counts: listing.counts,
+        }));
+    }
+}
+
+impl<R: BorrowMut<DirectoryReader>> Iterator for Walker<'_, R> {
+    type Item = WalkEvent;
+
+    fn next(&mut self) -> Option<WalkEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            if let Some(root) = self.root.take() {
+                self.enter(root, 1);
+                continue;
+            }
+
+            let frame = self.stack.last_mut()?;
+            if let Some(child) = frame.children.next() {
+                let depth = frame.depth + 1;
+                self.enter(child, depth);
+            } else {
+                self.stack.pop();
+                return Some(WalkEvent::Leave);
+            }
+        }
+    }
+}
+
+/// Source of directory reads: inline, or prefetched by a worker pool.
+pub(crate) enum DirectoryReader {
+    Serial(LspignoreCache, UsageCache),
+    Pool(ReadPool),
+}
+
+impl DirectoryReader {
+    /// Start a reader for one run; reads that sort by `--total-size` fill
+    /// the run's `usage` cache.
+    pub(crate) fn new(params: &Params, usage: &UsageCache) -> Self {
+        match params.traversal_threads() {
+            0 | 1 => Self::Serial(LspignoreCache::default(), usage.clone()),
+            threads => Self::Pool(ReadPool::new(threads, params, usage)),
+        }
+    }
+
+    /// Queue reads for directories the walker is about to enter in order.
+    fn prefetch(
+        &mut self,
+        paths: &[PathBuf],
+        depth: usize,
+        options: WalkOptions,
+    ) {
+        if let Self::Pool(pool) = self {
+            pool.plan(paths, depth, options);
+        }
+    }
+
+    fn read(
+        &mut self,
+        path: &Path,
+        depth: usize,
+        params: &Params,
+        options: WalkOptions,
+    ) -> DirectoryRead {
+        match self {
+            Self::Serial(lspignore_cache, usage) => read_directory(
+                path,
+                depth,
+                params,
+                options,
+                lspignore_cache,
+                usage,
+            ),
+            Self::Pool(pool) => pool.take(path, depth, options),
+        }
+    }
+
+    fn reset(&mut self) {
+        if let Self::Pool(pool) = self {
+            pool.reset();
+        }
+    }
+}
+
+/// Directories each read worker may have read or queued ahead of the walk.
+const READ_AHEAD_PER_THREAD: usize = 4;
+
+/// Worker threads that read directories ahead of the depth-first walk.
+///
+/// Directories the walker will enter are kept on a stack in the order it
+/// enters them: the children of the deepest entered directory come next.
+/// Only a window of them is handed to the workers at a time, oldest first,
+/// so reads that are done but not yet needed stay bounded by that window.
+pub(crate) struct ReadPool {
+    queue: Arc<ReadQueue>,
+    results: mpsc::Receiver<ReadResult>,
+    /// Directories the walker will enter, next one last.
+    planned: Vec<ReadJob>,
+    /// Directories handed to the workers and not yet taken by the walker.
+    in_flight: HashSet<PathBuf>,
+    ready: HashMap<PathBuf, DirectoryRead>,
+    window: usize,
+    /// Bumped on reset so reads for an abandoned walk can be told apart.
+    generation: u64,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct ReadJob {
+    path: PathBuf,
+    depth: usize,
+    options: WalkOptions,
+    generation: u64,
+}
+
+struct ReadResult {
+    path: PathBuf,
+    generation: u64,
+    read: DirectoryRead,
+}
+
+#[derive(Default)]
+struct ReadQueue {
+    state: Mutex<ReadQueueState>,
+    available: Condvar,
+}
+
+#[derive(Default)]
+struct ReadQueueState {
+    jobs: VecDeque<ReadJob>,
+    closed: bool,
+}
+
+impl ReadPool {
+    fn new(threads: usize, params: &Params, usage: &UsageCache) -> Self {
+        let queue = Arc::new(ReadQueue::default());
+        let params = Arc::new(params.clone());
+        let (sender, results) = mpsc::channel();
+        let workers = (0..threads)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let params = Arc::clone(&params);
+                let usage = usage.clone();
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    read_worker(&queue, &params, &usage, &sender);
+                })
+            })
+            .collect();
+
+        Self {
+            queue,
+            results,
+            planned: Vec::new(),
+            in_flight: HashSet::new(),
+            ready: HashMap::new(),
+            window: threads * READ_AHEAD_PER_THREAD,
+            generation: 0,
+            workers,
+        }
+    }
+
+    /// Record directories the walker will enter next, in entry order.
+    fn plan(&mut self, paths: &[PathBuf], depth: usize, options: WalkOptions) {
+        self.planned.extend(paths.iter().rev().map(|path| ReadJob {
+            path: path.clone(),
+            depth,
+            options,
+            generation: self.generation,
+        }));
+        self.fill_window();
+    }
+
+    fn take(
+        &mut self,
+        path: &Path,
+        depth: usize,
+        options: WalkOptions,
+    ) -> DirectoryRead {
+        if !self.in_flight.contains(path) {
+            // The root is never planned, and a planned directory may still be
+            // waiting outside the window.
+            let job =
+                match self.planned.iter().rposition(|job| job.path == path) {
+                    Some(index) => self.planned.remove(index),
+                    None => ReadJob {
+                        path: path.to_path_buf(),
+                        depth,
+                        options,
+                        generation: self.generation,
+                    },
+                };
+            self.send(job);
+        }
+
+        let read = self.wait_for(path);
+        self.in_flight.remove(path);
+        self.fill_window();
+        read
+    }
+
+    /// Forget every planned and outstanding read.
+    fn reset(&mut self) {
+        self.generation += 1;
+        self.planned.clear();
+        self.in_flight.clear();
+        self.ready.clear();
+        self.queue.state.lock().unwrap().jobs.clear();
+    }
+
+    fn wait_for(&mut self, path: &Path) -> DirectoryRead {
+        if let Some(read) = self.ready.remove(path) {
+            return read;
+        }
+
+        loop {
+            let Ok(result) = self.results.recv() else {
+                return DirectoryRead {
+                    identity: None,
+                    listing: Err(io::Error::other(
+                        "directory read workers stopped",
+                    )),
+                };
+            };
+            if result.generation != self.generation {
+                continue;
+            }
+            if result.path == path {
+                return result.read;
+            }
+            self.ready.insert(result.path, result.read);
+        }
+    }
+
+    fn fill_window(&mut self) {
+        while self.in_flight.len() < self.window {
+            let Some(job) = self.planned.pop() else {
+                break;
+            };
+            self.send(job);
+        }
+    }
+
+    fn send(&mut self, job: ReadJob) {
+        self.in_flight.insert(job.path.clone());
+        self.queue.state.lock().unwrap().jobs.push_back(job);
+        self.queue.available.notify_one();
+    }
+}
+
+impl Drop for ReadPool {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().closed = true;
+        self.queue.available.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn read_worker(
+    queue: &ReadQueue,
+    params: &Params,
+    usage: &UsageCache,
+    results: &mpsc::Sender<ReadResult>,
+) {
+    let mut lspignore_cache = LspignoreCache::default();
+
+    loop {
+        let job = {
+            let mut state = queue.state.lock().unwrap();
+            loop {
+                if state.closed {
+                    return;
+                }
+                if let Some(job) = state.jobs.pop_front() {
+                    break job;
+                }
+                state = queue.available.wait(state).unwrap();
+            }
+        };
+
+        let read = read_directory(
+            &job.path,
+            job.depth,
+            params,
+            job.options,
+            &mut lspignore_cache,
+            usage,
+        );
+        let result = ReadResult {
+            path: job.path,
+            generation: job.generation,
+            read,
+        };
+        if results.send(result).is_err() {
+            return;
+        }
+    }
+}
+
+/// Read, filter, and stat one directory and decide which entries to enter.
+fn read_directory(
+    path: &Path,
+    depth: usize,
+    params: &Params,
+    options: WalkOptions,
+    lspignore_cache: &mut LspignoreCache,
+    usage: &UsageCache,
+) -> DirectoryRead {
+    let identity = platform::file_identity(path).ok();
+    let names = match collect_file_names_with_usage(path, params, usage) {
+        Ok(names) => names,
+        Err(error) => {
+            return DirectoryRead {
+                identity,
+                listing: Err(error),
+            };
+        }
+    };
+
+    let filesystem = identity
+        .filter(|_| params.one_file_system)
+        .map(|identity| identity.device);
+    let may_descend = options.max_depth.is_none_or(|limit| depth < limit);
+    let mut entries = Vec::with_capacity(names.len());
+    let mut counts = ChildCounts::default();
+    let mut errors = Vec::new();
+
+    for name in names {
+        let dot_entry = matches!(name.as_str(), "." | "..");
+        if dot_entry && (depth > 1 || !options.root_dot_entries) {
+            continue;
+        }
+
+        let entry_path = path.join(&name);
+        let metadata = match entry_metadata(
+            &entry_path,
+            params.dereference.follows_entries(),
+        ) {
+            Ok(metadata) => metadata,
+            Err(error) => {
+                errors.push(WalkError {
+                    path: entry_path,
+                    error,
+                    root: false,
+                    cycle: false,
+                });
+                continue;
+            }
+        };
+        let directory = !dot_entry
+            && platform::classify_entry(&entry_path, &metadata).may_recurse;
+        if directory {
+            counts.directories += 1;
+        } else if !dot_entry {
+            counts.files += 1;
+        }
+        if options.directories_only && !directory {
+            continue;
+        }
+        let (name, entry_path, metadata, chain) =
+            if directory && options.collapse_chains {
+                collapse_chain(
+                    name,
+                    entry_path,
+                    metadata,
+                    params,
+                    filesystem,
+                    lspignore_cache,
+                    usage,
+                )
+            } else {
+                (name, entry_path, metadata, Vec::new())
+            };
+        let mount_point = directory && is_mount_point(&entry_path, filesystem);
+        let pruned = directory
+            && may_descend
+            && !mount_point
+            && should_prune(&entry_path, params, lspignore_cache);
+        let descend = directory && may_descend && !mount_point && !pruned;
+
+        entries.push(WalkEntry {
+            name,
+            path: entry_path,
+            metadata,
+            descend,
+            mount_point,
+            pruned,
+            chain,
+        });
+    }
+
+    let elided =
+        elide_entries(&mut entries, options.max_entries, params, usage);
+
+    DirectoryRead {
+        identity,
+        listing: Ok(DirectoryListing {
+            entries,
+            elided,
+            counts,
+            errors,
+        }),
+    }
+}
+
+/// Follow a directory down while it holds exactly one visible entry that is
+/// itself a directory.
+///
+/// The chain ends at anything the walker would not descend into, so a pruned
+/// directory or mount point is always the last component of a collapsed row.
+fn collapse_chain(
+    mut name: String,
+    mut path: PathBuf,
+    mut metadata: fs::Metadata,
+    params: &Params,
+    filesystem: Option<u64>,
+    lspignore_cache: &mut LspignoreCache,
+    usage: &UsageCache,
+) -> (String, PathBuf, fs::Metadata, Vec<String>) {
+    let mut chain = Vec::new();
+    let mut visited = HashSet::new();
+
+    loop {
+        if is_mount_point(&path, filesystem)
+            || should_prune(&path, params, lspignore_cache)
+            || platform::file_identity(&path)
+                .is_ok_and(|identity| !visited.insert(identity))
+        {
+            break;
+        }
+
+        let Ok(names) = collect_file_names_with_usage(&path, params, usage)
+        else {
+            break;
+        };
+        let mut names = names
+            .into_iter()
+            .filter(|name| !matches!(name.as_str(), "." | ".."));
+        let (Some(child_name), None) = (names.next(), names.next()) else {
+            break;
+        };
+        let child_path = path.join(&child_name);
+        let Ok(child_metadata) =
+            entry_metadata(&child_path, params.dereference.follows_entries())
+        else {
+            break;
+        };
+        if !platform::classify_entry(&child_path, &child_metadata).may_recurse
+        {
+            break;
+        }
+
+        chain.push(std::mem::replace(&mut name, child_name));
+        path = child_path;
+        metadata = child_metadata;
+    }
+
+    (name, path, metadata, chain)
+}
+
+/// Return whether a directory is listed without being descended into.
+///
+/// Directories are pruned by exact basename or by `.lspignore` rules; the
+/// latter still applies when `-a` makes the ignored entry itself visible.
+fn should_prune(
+    path: &Path,
+    params: &Params,
+    lspignore_cache: &mut LspignoreCache,
+) -> bool {
+    let pruned_by_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            params.prune_dirs.iter().any(|pruned| pruned == name)
+        });
+
+    pruned_by_name
+        || (params.lspignore && lspignore_cache.is_ignored(path, true))
+}
+
+/// Return whether a directory lives on a different device than `filesystem`.
+fn is_mount_point(path: &Path, filesystem: Option<u64>) -> bool {
+    filesystem.is_some_and(|device| {
+        platform::file_identity(path)
+            .is_ok_and(|identity| identity.device != device)
+    })
+}
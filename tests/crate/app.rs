@@ -1,11 +1,18 @@
 use crate::app::{
-    collect_listing_sections, collect_tree_sections, patterns_from_args,
+    EXIT_SERIOUS_TROUBLE, build_path_list_section, collect_listing_sections,
+    collect_tree_sections, collect_usage_sections, patterns_from_args,
     run_with_flags, run_with_flags_and_config,
 };
 use crate::cli::Flags;
 use crate::common_tests::ColorModeGuard;
-use crate::utils::color::{color_mode_for, long_format_color_level};
-use crate::{IconDisplay, Params};
+use crate::structs::{
+    Dereference, SortMode, SyntheticEntry, TotalSize, TreeStyle,
+};
+use crate::utils::color::{
+    color_mode_for, configure_color_output, long_format_color_level,
+};
+use crate::utils::render::UsageName;
+use crate::{ColorDisplay, HyperlinkDisplay, IconDisplay, Params};
 use colored_text::{ColorLevel, ColorMode};
 use std::fs;
 use tempfile::tempdir;
@@ -40,6 +47,33 @@ fn default_flags_with_paths(paths: Vec<String>) -> Flags {
         gitignore: false,
         version: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     }
 }
 
@@ -63,6 +97,25 @@ fn test_resolve_icon_output_uses_policy_and_destination() {
     }
 }
 
+#[test]
+fn test_resolve_hyperlink_output_uses_policy_and_destination() {
+    for (hyperlink, is_terminal, expected) in [
+        (HyperlinkDisplay::Auto, true, HyperlinkDisplay::Always),
+        (HyperlinkDisplay::Auto, false, HyperlinkDisplay::Never),
+        (HyperlinkDisplay::Always, false, HyperlinkDisplay::Always),
+        (HyperlinkDisplay::Never, true, HyperlinkDisplay::Never),
+    ] {
+        let mut params = Params {
+            hyperlink,
+            ..Params::default()
+        };
+
+        params.resolve_hyperlink_output(is_terminal);
+
+        assert_eq!(params.hyperlink, expected);
+    }
+}
+
 #[test]
 fn test_patterns_from_args_defaults_to_current_directory() {
     assert_eq!(patterns_from_args(Vec::new()), vec![String::from(".")]);
@@ -160,6 +213,33 @@ fn test_run_with_flags_lists_matching_entries() {
             gitignore: false,
             version: false,
             fuzzy_time: false,
+            no_lspignore: false,
+            dereference: false,
+            dereference_command_line: false,
+            one_file_system: false,
+            threads: None,
+            tree_style: None,
+            summary: false,
+            total_size: None,
+            usage: false,
+            collapse: false,
+            prune_empty: false,
+            dirs_only: false,
+            from_stdin: false,
+            null_separated: false,
+            max_entries: None,
+            quoting_style: None,
+            hide_control_chars: None,
+            numeric_uid_gid: false,
+            no_owner: false,
+            no_group: false,
+            directory: false,
+            dired: false,
+            hyperlink: None,
+            zero: false,
+            width: None,
+            tabsize: None,
+            color: None,
         };
 
         assert!(run_with_flags(flags).is_ok());
@@ -181,6 +261,58 @@ fn test_run_with_flags_and_config_rejects_tree_and_recursive_merge() {
     assert!(err.to_string().contains("--tree and --recursive"));
 }
 
+#[test]
+fn test_run_with_flags_and_config_rejects_tree_with_directory() {
+    let mut flags = default_flags_with_paths(Vec::new());
+    flags.directory = true;
+    let config = Params {
+        tree: true,
+        ..Params::default()
+    };
+
+    let err = run_with_flags_and_config(flags, &config).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("--tree and --directory"));
+}
+
+#[test]
+fn test_run_with_flags_and_config_rejects_usage_with_directory() {
+    let mut flags = default_flags_with_paths(Vec::new());
+    flags.directory = true;
+    let config = Params {
+        usage: true,
+        ..Params::default()
+    };
+
+    let err = run_with_flags_and_config(flags, &config).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("--usage and --directory"));
+}
+
+#[test]
+fn test_run_with_flags_and_config_rejects_dired_trees() {
+    for config in [
+        Params {
+            tree: true,
+            ..Params::default()
+        },
+        Params {
+            usage: true,
+            ..Params::default()
+        },
+    ] {
+        let mut flags = default_flags_with_paths(Vec::new());
+        flags.dired = true;
+
+        let err = run_with_flags_and_config(flags, &config).unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("--dired"));
+    }
+}
+
 #[test]
 fn test_run_with_flags_renders_multiple_long_directory_sections() {
     let temp_dir = tempdir().unwrap();
@@ -236,6 +368,38 @@ fn test_run_with_flags_renders_recursive_long_sections() {
     assert!(run_with_flags_and_config(flags, &Params::default()).is_ok());
 }
 
+#[cfg(unix)]
+#[test]
+fn test_run_with_flags_reports_directory_cycles_in_exit_status() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    std::os::unix::fs::symlink(temp_dir.path(), nested.join("loop")).unwrap();
+
+    for tree in [false, true] {
+        let mut flags = default_flags_with_paths(vec![
+            temp_dir.path().display().to_string(),
+        ]);
+        flags.recursive = !tree;
+        flags.tree = tree;
+        flags.tree_level = Some(4);
+        flags.dereference = true;
+
+        assert_eq!(
+            run_with_flags_and_config(flags, &Params::default()).unwrap(),
+            EXIT_SERIOUS_TROUBLE
+        );
+    }
+
+    let flags =
+        default_flags_with_paths(vec![temp_dir.path().display().to_string()]);
+
+    assert_eq!(
+        run_with_flags_and_config(flags, &Params::default()).unwrap(),
+        0
+    );
+}
+
 #[test]
 fn test_collect_listing_sections_groups_multiple_directories() {
     let temp_dir = tempdir().unwrap();
@@ -290,6 +454,35 @@ fn test_collect_listing_sections_puts_files_before_directories() {
     assert_eq!(sections[1].header, Some(dir.display().to_string()));
 }
 
+#[test]
+fn test_collect_listing_sections_lists_directory_operands_as_entries() {
+    let temp_dir = tempdir().unwrap();
+    let left = temp_dir.path().join("left");
+    let right = temp_dir.path().join("right");
+    fs::create_dir(&left).unwrap();
+    fs::create_dir(&right).unwrap();
+    fs::write(left.join("alpha.txt"), "alpha").unwrap();
+    let params = Params {
+        directory: true,
+        ..Params::default()
+    };
+
+    let sections = collect_listing_sections(
+        &[temp_dir.path().join("*").display().to_string()],
+        &params,
+    )
+    .unwrap();
+
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].header, None);
+    let names = sections[0]
+        .entries
+        .iter()
+        .map(|info| info.short_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["left", "right"]);
+}
+
 #[test]
 fn test_collect_listing_sections_keeps_single_directory_unlabeled() {
     let temp_dir = tempdir().unwrap();
@@ -305,6 +498,36 @@ fn test_collect_listing_sections_keeps_single_directory_unlabeled() {
     assert_eq!(sections[0].header, None);
 }
 
+#[test]
+fn test_collect_listing_sections_sorts_by_directory_totals() {
+    let temp_dir = tempdir().unwrap();
+    let heavy = temp_dir.path().join("heavy");
+    fs::create_dir(&heavy).unwrap();
+    fs::write(heavy.join("data.bin"), "x".repeat(64)).unwrap();
+    fs::write(temp_dir.path().join("medium.txt"), "x".repeat(32)).unwrap();
+    fs::write(temp_dir.path().join("small.txt"), "x").unwrap();
+    let params = Params {
+        long_format: true,
+        no_icons: true,
+        sort: SortMode::Size,
+        total_size: Some(TotalSize::Apparent),
+        ..Params::default()
+    };
+
+    let sections = collect_listing_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+    let rows = sections[0]
+        .entries
+        .iter()
+        .map(|info| (info.short_name.as_str(), info.size))
+        .collect::<Vec<_>>();
+
+    assert_eq!(rows, [("heavy", 64), ("medium.txt", 32), ("small.txt", 1)]);
+}
+
 #[test]
 fn test_collect_listing_sections_recurses_with_headers() {
     let temp_dir = tempdir().unwrap();
@@ -846,6 +1069,80 @@ fn test_collect_listing_sections_recursive_prunes_custom_directories() {
     );
 }
 
+#[test]
+fn test_collect_listing_sections_recursive_hides_and_prunes_lspignored_dirs() {
+    let temp_dir = tempdir().unwrap();
+    let generated = temp_dir.path().join("generated");
+    fs::create_dir(&generated).unwrap();
+    fs::write(generated.join("output.txt"), "output").unwrap();
+    fs::write(temp_dir.path().join(".lspignore"), "generated/\n").unwrap();
+    let params = Params {
+        recursive: true,
+        ..Params::default()
+    };
+
+    let sections = collect_listing_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+
+    assert_eq!(sections.len(), 1);
+    assert!(
+        !sections[0]
+            .entries
+            .iter()
+            .any(|info| info.display_name.contains("generated"))
+    );
+
+    let params = Params {
+        recursive: true,
+        almost_all: true,
+        ..Params::default()
+    };
+    let sections = collect_listing_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+
+    assert_eq!(sections.len(), 1);
+    assert!(
+        sections[0]
+            .entries
+            .iter()
+            .any(|info| info.display_name.contains("generated"))
+    );
+}
+
+#[test]
+fn test_collect_listing_sections_recursive_can_disable_lspignore() {
+    let temp_dir = tempdir().unwrap();
+    let generated = temp_dir.path().join("generated");
+    fs::create_dir(&generated).unwrap();
+    fs::write(generated.join("output.txt"), "output").unwrap();
+    fs::write(temp_dir.path().join(".lspignore"), "generated/\n").unwrap();
+    let params = Params {
+        recursive: true,
+        lspignore: false,
+        ..Params::default()
+    };
+
+    let sections = collect_listing_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+
+    assert_eq!(sections.len(), 2);
+    assert!(
+        sections[1]
+            .entries
+            .iter()
+            .any(|info| info.display_name.contains("output.txt"))
+    );
+}
+
 #[test]
 fn test_collect_tree_sections_uses_level_limit() {
     let temp_dir = tempdir().unwrap();
@@ -974,6 +1271,62 @@ fn test_collect_tree_sections_keeps_vertical_prefix_for_non_last_branch() {
     assert!(other_entry.name_prefix.starts_with("    "));
 }
 
+/// Return `(prefix, name)` rows for a two-level tree in the given style.
+fn tree_rows_with_style(style: TreeStyle) -> Vec<(String, String)> {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir_all(temp_dir.path().join("a-dir/inner")).unwrap();
+    fs::write(temp_dir.path().join("a-dir/leaf.txt"), "leaf").unwrap();
+    fs::write(temp_dir.path().join("b-file.txt"), "file").unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        tree_style: style,
+        ..Params::default()
+    };
+
+    let sections = collect_tree_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+
+    sections[0]
+        .entries
+        .iter()
+        .map(|entry| {
+            (entry.name_prefix.clone(), entry.info.short_name.clone())
+        })
+        .collect()
+}
+
+#[test]
+fn test_collect_tree_sections_draws_each_tree_style() {
+    let rows = |style| {
+        tree_rows_with_style(style)
+            .into_iter()
+            .map(|(prefix, _)| prefix)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(rows(TreeStyle::Compact), ["", "├── ", "└── ", ""]);
+    assert_eq!(rows(TreeStyle::Ascii), ["", "|-- ", "`-- ", ""]);
+    assert_eq!(rows(TreeStyle::Indent), ["", "    ", "    ", ""]);
+    assert_eq!(
+        rows(TreeStyle::Classic),
+        ["", "├── ", "│   ├── ", "│   └── ", "└── "]
+    );
+}
+
+#[test]
+fn test_collect_tree_sections_classic_style_starts_with_root_row() {
+    let rows = tree_rows_with_style(TreeStyle::Classic);
+
+    assert_eq!(rows[1].1, "a-dir");
+    assert_eq!(rows[4].1, "b-file.txt");
+    assert!(rows[0].0.is_empty());
+}
+
 #[test]
 fn test_collect_tree_sections_handles_file_operand_and_missing_operand() {
     let temp_dir = tempdir().unwrap();
@@ -1094,6 +1447,323 @@ fn test_collect_tree_sections_prunes_noisy_directory_descendants() {
     );
 }
 
+#[test]
+fn test_collect_tree_sections_summarizes_visible_entries() {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir_all(temp_dir.path().join("src/deep")).unwrap();
+    fs::create_dir_all(temp_dir.path().join("target/debug")).unwrap();
+    fs::write(temp_dir.path().join("src/main.rs"), "main").unwrap();
+    fs::write(temp_dir.path().join("src/deep/hidden.rs"), "x").unwrap();
+    fs::write(temp_dir.path().join("README"), "readme").unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        prune_dirs: vec![String::from("target")],
+        ..Params::default()
+    };
+
+    let sections = collect_tree_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+    let summary = sections[0].summary;
+
+    assert_eq!(summary.directories, 3);
+    assert_eq!(summary.pruned, 1);
+    assert_eq!(summary.files, 2);
+    assert_eq!(summary.bytes, 10);
+    assert_eq!(summary.errors, 0);
+}
+
+#[test]
+fn test_collect_usage_sections_shows_shares_and_folds_small_entries() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    fs::write(nested.join("inner.bin"), "x".repeat(500)).unwrap();
+    fs::write(temp_dir.path().join("big.bin"), "x".repeat(1000)).unwrap();
+    fs::write(temp_dir.path().join("tiny-a"), "x").unwrap();
+    fs::write(temp_dir.path().join("tiny-b"), "x").unwrap();
+    let params = Params::merge(
+        &Flags::parse_from(["lsplus", "--usage", "--no-icons"]),
+        &Params::default(),
+    );
+
+    let sections = collect_usage_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+    let rows = sections[0]
+        .rows
+        .iter()
+        .map(|row| {
+            let name = match &row.name {
+                UsageName::Entry(info) => info.short_name.clone(),
+                UsageName::Others(count) => format!("{count} others"),
+            };
+            (row.name_prefix.clone(), name, row.size)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[0].2, 1502);
+    assert_eq!(
+        rows[1],
+        (String::from("├── "), String::from("big.bin"), 1000)
+    );
+    assert_eq!(rows[2], (String::from("├── "), String::from("nested"), 500));
+    assert_eq!(
+        rows[3],
+        (String::from("│   └── "), String::from("inner.bin"), 500)
+    );
+    assert_eq!(rows[4], (String::from("└── "), String::from("2 others"), 2));
+    assert!((sections[0].rows[2].share - 500.0 / 1502.0).abs() < 1e-9);
+    assert!((sections[0].rows[3].share - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_collect_tree_sections_collapses_directory_chains_on_one_row() {
+    let temp_dir = tempdir().unwrap();
+    let app = temp_dir.path().join("src/main/java/com/acme/app");
+    fs::create_dir_all(&app).unwrap();
+    fs::write(app.join("Main.java"), "main").unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        collapse: true,
+        ..Params::default()
+    };
+
+    let sections = collect_tree_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+    let rows = sections[0]
+        .entries
+        .iter()
+        .map(|entry| format!("{}{}", entry.name_prefix, entry.info.short_name))
+        .collect::<Vec<_>>();
+
+    assert_eq!(rows, ["src/main/java/com/acme/app", "└── Main.java"]);
+    assert_eq!(sections[0].summary.directories, 6);
+    assert_eq!(sections[0].entries[0].info.full_path, app);
+}
+
+#[test]
+fn test_collect_tree_sections_prune_empty_keeps_only_populated_branches() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("empty/nested")).unwrap();
+    fs::create_dir_all(root.join("src/bin")).unwrap();
+    fs::create_dir_all(root.join("target/debug")).unwrap();
+    fs::write(root.join("src/bin/main.rs"), "main").unwrap();
+    fs::write(root.join("src/.hidden"), "hidden").unwrap();
+    fs::create_dir(root.join("src/gen")).unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        prune_empty: true,
+        prune_dirs: vec![String::from("target")],
+        tree_level: 5,
+        ..Params::default()
+    };
+
+    let sections =
+        collect_tree_sections(&[root.display().to_string()], &params).unwrap();
+    let rows = sections[0]
+        .entries
+        .iter()
+        .map(|entry| format!("{}{}", entry.name_prefix, entry.info.short_name))
+        .collect::<Vec<_>>();
+
+    assert_eq!(rows, ["src", "└── bin", "    └── main.rs", "target"]);
+    assert_eq!(sections[0].summary.directories, 3);
+    assert_eq!(sections[0].summary.files, 1);
+}
+
+#[test]
+fn test_collect_tree_sections_dirs_only_annotates_child_counts() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/bin/deep")).unwrap();
+    fs::create_dir_all(root.join("target/debug")).unwrap();
+    fs::write(root.join("src/lib.rs"), "lib").unwrap();
+    fs::write(root.join("src/bin/main.rs"), "main").unwrap();
+    fs::write(root.join("README.md"), "readme").unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        dirs_only: true,
+        prune_dirs: vec![String::from("target")],
+        tree_level: 2,
+        ..Params::default()
+    };
+
+    let sections =
+        collect_tree_sections(&[root.display().to_string()], &params).unwrap();
+    let rows = sections[0]
+        .entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}{}",
+                entry.name_prefix,
+                strip_ansi_escapes::strip_str(&entry.info.display_name)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        rows,
+        ["src (1 file, 1 dir)", "└── bin (1 file, 1 dir)", "target"]
+    );
+    assert_eq!(sections[0].summary.directories, 3);
+    assert_eq!(sections[0].summary.files, 0);
+}
+
+#[test]
+fn test_build_path_list_section_nests_paths_in_listed_order() {
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        sort: SortMode::None,
+        tree_level: 5,
+        ..Params::default()
+    };
+    let input = "lsp-virtual/b.txt\r\n./lsp-virtual/a/c.rs\n\nnotes.md\n";
+
+    let section =
+        build_path_list_section(input.as_bytes(), false, &params).unwrap();
+    let rows = section
+        .entries
+        .iter()
+        .map(|entry| format!("{}{}", entry.name_prefix, entry.info.short_name))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        rows,
+        [
+            "lsp-virtual",
+            "├── b.txt",
+            "└── a",
+            "    └── c.rs",
+            "notes.md"
+        ]
+    );
+    assert!(section.entries.iter().all(|entry| entry.info.synthetic == Some(SyntheticEntry::Virtual)));
+    assert_eq!(section.summary.directories, 2);
+    assert_eq!(section.summary.files, 3);
+}
+
+#[test]
+fn test_build_path_list_section_stats_existing_paths() {
+    let temp_dir = tempdir().unwrap();
+    let file = temp_dir.path().join("listed.txt");
+    fs::write(&file, "listed").unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        tree_level: 1,
+        ..Params::default()
+    };
+    let input = format!("{}\0", file.display());
+
+    let section =
+        build_path_list_section(input.as_bytes(), true, &params).unwrap();
+
+    assert_eq!(section.entries.len(), 1);
+    assert_eq!(section.entries[0].info.synthetic, None);
+    assert_eq!(section.summary.directories, 1);
+
+    let params = Params {
+        tree_level: 32,
+        ..params
+    };
+    let section =
+        build_path_list_section(input.as_bytes(), true, &params).unwrap();
+    let last = section.entries.last().unwrap();
+
+    assert_eq!(last.info.full_path, file);
+    assert_eq!(last.info.size, 6);
+    assert_eq!(last.info.synthetic, None);
+}
+
+#[test]
+fn test_collect_tree_sections_elides_entries_past_max_entries() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("logs")).unwrap();
+    for name in ["1.log", "2.log", "3.log", "4.log"] {
+        fs::write(root.join("logs").join(name), "log").unwrap();
+    }
+    fs::write(root.join("readme"), "readme").unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        max_entries: Some(2),
+        ..Params::default()
+    };
+
+    let sections =
+        collect_tree_sections(&[root.display().to_string()], &params).unwrap();
+    let rows = sections[0]
+        .entries
+        .iter()
+        .map(|entry| format!("{}{}", entry.name_prefix, entry.info.short_name))
+        .collect::<Vec<_>>();
+    let elided = &sections[0].entries[3].info;
+
+    assert_eq!(
+        rows,
+        [
+            "logs",
+            "├── 1.log",
+            "├── 2.log",
+            "└── … 2 more entries",
+            "readme"
+        ]
+    );
+    assert_eq!(elided.synthetic, Some(SyntheticEntry::Elided(2)));
+    assert_eq!(elided.size, 6);
+    assert_eq!(sections[0].summary.files, 3);
+}
+
+#[test]
+fn test_collect_listing_sections_elides_recursive_entries() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("skipped")).unwrap();
+    fs::write(root.join("skipped/inner.txt"), "inner").unwrap();
+    fs::write(root.join("a.txt"), "a").unwrap();
+    let params = Params {
+        recursive: true,
+        max_entries: Some(1),
+        ..Params::default()
+    };
+
+    let sections =
+        collect_listing_sections(&[root.display().to_string()], &params)
+            .unwrap();
+    let names = sections[0]
+        .entries
+        .iter()
+        .map(|info| info.short_name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(sections.len(), 1);
+    assert_eq!(names, ["a.txt", "… 1 more entry"]);
+}
+
 #[test]
 fn test_collect_tree_sections_prunes_custom_directory_descendants() {
     let temp_dir = tempdir().unwrap();
@@ -1129,6 +1799,42 @@ fn test_collect_tree_sections_prunes_custom_directory_descendants() {
     );
 }
 
+#[test]
+fn test_collect_tree_sections_uses_lspignore_from_parent_directories() {
+    let temp_dir = tempdir().unwrap();
+    let project = temp_dir.path().join("project");
+    let build = project.join("build");
+    fs::create_dir_all(&build).unwrap();
+    fs::write(build.join("artifact.o"), "artifact").unwrap();
+    fs::write(project.join("main.rs"), "fn main() {}").unwrap();
+    fs::write(temp_dir.path().join(".lspignore"), "build\n").unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        show_all: true,
+        ..Params::default()
+    };
+
+    let sections =
+        collect_tree_sections(&[project.display().to_string()], &params)
+            .unwrap();
+
+    assert_eq!(sections.len(), 1);
+    assert!(
+        sections[0]
+            .entries
+            .iter()
+            .any(|entry| entry.info.display_name.contains("build"))
+    );
+    assert!(
+        !sections[0]
+            .entries
+            .iter()
+            .any(|entry| entry.info.display_name.contains("artifact.o"))
+    );
+}
+
 #[cfg(unix)]
 #[test]
 fn test_collect_tree_sections_skips_symlinked_directory_descendants() {
@@ -1166,6 +1872,173 @@ fn test_collect_tree_sections_skips_symlinked_directory_descendants() {
     );
 }
 
+#[cfg(unix)]
+#[test]
+fn test_collect_tree_sections_follows_symlinked_directories_with_dereference()
+{
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path().join("root");
+    let real = temp_dir.path().join("real");
+    fs::create_dir(&root).unwrap();
+    fs::create_dir(&real).unwrap();
+    fs::write(real.join("deep.txt"), "deep").unwrap();
+    std::os::unix::fs::symlink(&real, root.join("link")).unwrap();
+    std::os::unix::fs::symlink(&root, real.join("back")).unwrap();
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        tree_level: 10,
+        dereference: Dereference::Always,
+        ..Params::default()
+    };
+
+    let sections =
+        collect_tree_sections(&[root.display().to_string()], &params).unwrap();
+    let names = sections[0]
+        .entries
+        .iter()
+        .map(|entry| entry.info.short_name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, vec!["link", "back", "deep.txt"]);
+    assert_eq!(sections[0].entries[0].info.file_type, "d");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_collect_listing_sections_recursive_stops_at_symlink_cycles() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    std::os::unix::fs::symlink(temp_dir.path(), nested.join("loop")).unwrap();
+    let params = Params {
+        recursive: true,
+        no_icons: true,
+        dereference: Dereference::Always,
+        ..Params::default()
+    };
+
+    let sections = collect_listing_sections(
+        &[temp_dir.path().display().to_string()],
+        &params,
+    )
+    .unwrap();
+
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[1].entries[0].short_name, "loop");
+    assert_eq!(sections[1].entries[0].file_type, "d");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_collect_listing_sections_dereferences_only_command_line_links() {
+    let temp_dir = tempdir().unwrap();
+    let target = temp_dir.path().join("target.txt");
+    let operand = temp_dir.path().join("operand");
+    let listed = temp_dir.path().join("listed");
+    fs::create_dir(&listed).unwrap();
+    fs::write(&target, "target").unwrap();
+    std::os::unix::fs::symlink(&target, &operand).unwrap();
+    std::os::unix::fs::symlink(&target, listed.join("child")).unwrap();
+    let params = Params {
+        long_format: true,
+        no_icons: true,
+        dereference: Dereference::CommandLine,
+        ..Params::default()
+    };
+
+    let sections = collect_listing_sections(
+        &[operand.display().to_string(), listed.display().to_string()],
+        &params,
+    )
+    .unwrap();
+
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].entries[0].file_type, "-");
+    assert_eq!(sections[0].entries[0].size, 6);
+    assert_eq!(sections[1].entries[0].file_type, "l");
+}
+
+#[cfg(target_os = "linux")]
+fn proc_is_separate_filesystem() -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::metadata("/"), fs::metadata("/proc")) {
+        (Ok(root), Ok(proc)) => root.dev() != proc.dev(),
+        _ => false,
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_collect_tree_sections_marks_mount_points_with_one_file_system() {
+    if !proc_is_separate_filesystem() {
+        return;
+    }
+    let params = Params {
+        tree: true,
+        long_format: true,
+        no_icons: true,
+        tree_level: 2,
+        one_file_system: true,
+        ..Params::default()
+    };
+
+    let sections =
+        collect_tree_sections(&[String::from("/")], &params).unwrap();
+    let proc_index = sections[0]
+        .entries
+        .iter()
+        .position(|entry| entry.info.short_name == "proc")
+        .unwrap();
+
+    assert!(
+        sections[0].entries[proc_index]
+            .info
+            .display_name
+            .contains("[Mount Point]")
+    );
+    assert!(
+        sections[0]
+            .entries
+            .get(proc_index + 1)
+            .is_none_or(|entry| entry.name_prefix.is_empty())
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_collect_listing_sections_recursive_stays_on_one_file_system() {
+    if !proc_is_separate_filesystem() {
+        return;
+    }
+    let params = Params {
+        recursive: true,
+        recursive_level: Some(2),
+        no_icons: true,
+        one_file_system: true,
+        ..Params::default()
+    };
+
+    let sections =
+        collect_listing_sections(&[String::from("/")], &params).unwrap();
+
+    let proc = sections[0]
+        .entries
+        .iter()
+        .find(|info| info.short_name == "proc")
+        .unwrap();
+
+    assert!(proc.mount_point);
+    assert!(proc.display_name.contains("[Mount Point]"));
+    assert!(
+        !sections
+            .iter()
+            .any(|section| section.header.as_deref() == Some("/proc"))
+    );
+}
+
 #[test]
 fn test_color_mode_for_uses_never_when_no_color_is_enabled() {
     let params = Params {
@@ -1181,6 +2054,22 @@ fn test_color_mode_for_uses_auto_by_default() {
     assert_eq!(color_mode_for(&Params::default()), ColorMode::Auto);
 }
 
+#[test]
+fn test_color_mode_for_follows_color_setting() {
+    for (color, mode) in [
+        (ColorDisplay::Auto, ColorMode::Auto),
+        (ColorDisplay::Always, ColorMode::Always),
+        (ColorDisplay::Never, ColorMode::Never),
+    ] {
+        let params = Params {
+            color,
+            ..Params::default()
+        };
+
+        assert_eq!(color_mode_for(&params), mode);
+    }
+}
+
 #[test]
 fn test_long_format_color_level_uses_none_when_color_is_disabled() {
     temp_env::with_var("NO_COLOR", None::<&str>, || {
@@ -1234,6 +2123,46 @@ fn with_terminal_capability_env<T>(
     )
 }
 
+#[test]
+fn test_configure_color_output_resolves_color_once_for_stdout() {
+    with_terminal_capability_env(None, Some("xterm-256color"), || {
+        let _guard = ColorModeGuard::set(ColorMode::Auto);
+        let mut params = Params {
+            color: ColorDisplay::Always,
+            ..Params::default()
+        };
+
+        configure_color_output(&mut params, false);
+
+        assert_eq!(params.color, ColorDisplay::Always);
+        assert!(!params.no_color);
+        assert_eq!(long_format_color_level(&params), ColorLevel::Ansi256);
+
+        let mut params = Params::default();
+
+        configure_color_output(&mut params, false);
+
+        assert_eq!(params.color, ColorDisplay::Never);
+        assert!(params.no_color);
+        assert_eq!(long_format_color_level(&params), ColorLevel::NoColor);
+    });
+}
+
+#[test]
+fn test_configure_color_output_honors_clicolor_force_when_auto() {
+    with_terminal_capability_env(None, Some("xterm"), || {
+        temp_env::with_var("CLICOLOR_FORCE", Some("1"), || {
+            let _guard = ColorModeGuard::set(ColorMode::Auto);
+            let mut params = Params::default();
+
+            configure_color_output(&mut params, false);
+
+            assert_eq!(params.color, ColorDisplay::Always);
+            assert_eq!(long_format_color_level(&params), ColorLevel::Ansi16);
+        });
+    });
+}
+
 #[test]
 fn test_long_format_color_level_detects_terminal_capability() {
     with_terminal_capability_env(None, Some("dumb"), || {
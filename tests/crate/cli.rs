@@ -2,8 +2,9 @@ use crate::cli::{
     CompatMode, Flags, format_version_info, try_parse_from_mode, version_info,
 };
 use crate::{
-    IconDisplay, IndicatorStyle, ShortFormat, SortMode,
-    structs::{AttributeDisplay, PermissionDisplay},
+    ColorDisplay, HyperlinkDisplay, IconDisplay, IndicatorStyle, QuotingStyle,
+    ShortFormat, SortMode,
+    structs::{AttributeDisplay, PermissionDisplay, TotalSize, TreeStyle},
 };
 use clap::error::ErrorKind;
 
@@ -35,6 +36,11 @@ fn test_default_flags() {
     assert!(!args.no_time_gradient);
     assert!(!args.no_size_colors);
     assert!(!args.gitignore);
+    assert!(!args.no_lspignore);
+    assert!(!args.dereference);
+    assert!(!args.dereference_command_line);
+    assert!(!args.one_file_system);
+    assert_eq!(args.threads, None);
     assert!(!args.version);
     assert!(!args.fuzzy_time);
     assert_eq!(args.paths, vec![String::from(".")]);
@@ -111,6 +117,74 @@ fn test_tree_flags() {
     assert_eq!(args.tree_level, Some(3));
 }
 
+#[test]
+fn test_dereference_flags() {
+    let args = Flags::parse_from(["lsplus", "-L", "-H"]);
+
+    assert!(args.dereference);
+    assert!(args.dereference_command_line);
+
+    let args = Flags::parse_from([
+        "lsplus",
+        "--dereference",
+        "--dereference-command-line",
+    ]);
+
+    assert!(args.dereference);
+    assert!(args.dereference_command_line);
+}
+
+#[test]
+fn test_one_file_system_flag() {
+    let args = Flags::parse_from(["lsplus", "--one-file-system"]);
+
+    assert!(args.one_file_system);
+}
+
+#[test]
+fn test_total_size_flag_defaults_to_apparent() {
+    let args = Flags::parse_from(["lsplus", "--total-size"]);
+    assert_eq!(args.total_size, Some(TotalSize::Apparent));
+
+    let args = Flags::parse_from(["lsplus", "--total-size=disk"]);
+    assert_eq!(args.total_size, Some(TotalSize::Disk));
+}
+
+#[test]
+fn test_usage_flag_conflicts_with_other_traversals() {
+    let args = Flags::parse_from(["lsplus", "--usage", "--level", "3"]);
+    assert!(args.usage);
+    assert_eq!(args.tree_level, Some(3));
+
+    let err = try_parse_from_mode(
+        CompatMode::Native,
+        ["lsplus", "--usage", "--tree"],
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+}
+
+#[test]
+fn test_tree_style_flag() {
+    let args = Flags::parse_from(["lsplus", "--tree", "--tree-style=ascii"]);
+
+    assert_eq!(args.tree_style, Some(TreeStyle::Ascii));
+}
+
+#[test]
+fn test_threads_flag() {
+    let args = Flags::parse_from(["lsplus", "--threads", "4"]);
+
+    assert_eq!(args.threads, Some(4));
+}
+
+#[test]
+fn test_no_lspignore_flag() {
+    let args = Flags::parse_from(["lsplus", "--no-lspignore"]);
+
+    assert!(args.no_lspignore);
+}
+
 #[test]
 fn test_header_flag() {
     let args = Flags::parse_from(["lsplus", "--header"]);
@@ -253,6 +327,81 @@ fn test_parse_from_mode_accepts_recursive_and_tree_options() {
     }
 }
 
+#[test]
+fn test_parse_from_mode_accepts_directory_option() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        for option in ["-d", "--directory"] {
+            let args = try_parse_from_mode(mode, ["lsplus", option]).unwrap();
+
+            assert!(args.directory);
+        }
+
+        assert!(
+            try_parse_from_mode(mode, ["lsplus", "-d", "--from-stdin"])
+                .is_err()
+        );
+    }
+}
+
+#[test]
+fn test_parse_from_mode_accepts_dired_option() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        let args = try_parse_from_mode(mode, ["lsplus", "--dired"]).unwrap();
+
+        assert!(args.dired);
+        assert!(
+            try_parse_from_mode(mode, ["lsplus", "--dired", "--from-stdin"])
+                .is_err()
+        );
+    }
+}
+
+#[test]
+fn test_parse_from_mode_gnu_accepts_short_dired_option() {
+    let args = try_parse_from_mode(CompatMode::Gnu, ["lsplus", "-D"]).unwrap();
+
+    assert!(args.dired);
+    assert!(!args.dirs_first);
+
+    let args =
+        try_parse_from_mode(CompatMode::Native, ["lsplus", "-D"]).unwrap();
+
+    assert!(!args.dired);
+    assert!(args.dirs_first);
+}
+
+#[test]
+fn test_parse_from_mode_accepts_width_and_tabsize_options() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        let args =
+            try_parse_from_mode(mode, ["lsplus", "-w", "0", "--tabsize=4"])
+                .unwrap();
+
+        assert_eq!(args.width, Some(0));
+        assert_eq!(args.tabsize, Some(4));
+
+        let args = try_parse_from_mode(mode, ["lsplus", "--width=120", "-T0"])
+            .unwrap();
+
+        assert_eq!(args.width, Some(120));
+        assert_eq!(args.tabsize, Some(0));
+        assert!(try_parse_from_mode(mode, ["lsplus", "-w", "wide"]).is_err());
+    }
+}
+
+#[test]
+fn test_parse_from_mode_accepts_zero_option() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        let args = try_parse_from_mode(mode, ["lsplus", "--zero"]).unwrap();
+
+        assert!(args.zero);
+        assert!(
+            try_parse_from_mode(mode, ["lsplus", "--zero", "--dired"])
+                .is_err()
+        );
+    }
+}
+
 #[test]
 fn test_parse_from_mode_accepts_header_option() {
     for mode in [CompatMode::Native, CompatMode::Gnu] {
@@ -423,22 +572,114 @@ fn test_parse_from_mode_accepts_across_short_format_options() {
 }
 
 #[test]
-fn test_parse_from_mode_keeps_short_format_selector_precedence() {
+fn test_parse_from_mode_accepts_commas_and_single_column_formats() {
     for mode in [CompatMode::Native, CompatMode::Gnu] {
-        let vertical =
-            try_parse_from_mode(mode, ["lsplus", "-x", "-C"]).unwrap();
-        assert_eq!(vertical.short_format, Some(ShortFormat::Vertical));
+        let short = try_parse_from_mode(mode, ["lsplus", "-m"]).unwrap();
+        assert_eq!(short.short_format, Some(ShortFormat::Commas));
 
-        let fixed_precedence =
-            try_parse_from_mode(mode, ["lsplus", "-C", "-x"]).unwrap();
-        assert_eq!(fixed_precedence.short_format, Some(ShortFormat::Vertical));
+        let long = try_parse_from_mode(mode, ["lsplus", "--format", "commas"])
+            .unwrap();
+        assert_eq!(long.short_format, Some(ShortFormat::Commas));
 
-        let explicit = try_parse_from_mode(
-            mode,
-            ["lsplus", "-C", "-x", "--format", "across"],
+        let short = try_parse_from_mode(mode, ["lsplus", "-1"]).unwrap();
+        assert_eq!(short.short_format, Some(ShortFormat::SingleColumn));
+
+        let long =
+            try_parse_from_mode(mode, ["lsplus", "--format=single-column"])
+                .unwrap();
+        assert_eq!(long.short_format, Some(ShortFormat::SingleColumn));
+    }
+}
+
+#[test]
+fn test_parse_from_mode_native_keeps_short_format_selector_precedence() {
+    let mode = CompatMode::Native;
+    let vertical = try_parse_from_mode(mode, ["lsplus", "-x", "-C"]).unwrap();
+    assert_eq!(vertical.short_format, Some(ShortFormat::Vertical));
+
+    let fixed_precedence =
+        try_parse_from_mode(mode, ["lsplus", "-C", "-x"]).unwrap();
+    assert_eq!(fixed_precedence.short_format, Some(ShortFormat::Vertical));
+
+    let explicit =
+        try_parse_from_mode(mode, ["lsplus", "--format", "across", "-C"])
+            .unwrap();
+    assert_eq!(explicit.short_format, Some(ShortFormat::Across));
+
+    let long = try_parse_from_mode(mode, ["lsplus", "-l", "-1"]).unwrap();
+    assert!(long.long);
+}
+
+#[test]
+fn test_parse_from_mode_gnu_format_selectors_last_option_wins() {
+    let cases: [(&[&str], bool, Option<ShortFormat>); 10] = [
+        (&["-l", "-C"], false, Some(ShortFormat::Vertical)),
+        (&["-C", "-l"], true, None),
+        (&["-x", "-C"], false, Some(ShortFormat::Vertical)),
+        (&["-C", "-x"], false, Some(ShortFormat::Across)),
+        (&["-l1", "-m"], false, Some(ShortFormat::Commas)),
+        (&["-m", "--format=verbose"], true, None),
+        (&["--format", "long", "-1"], true, None),
+        (&["-l", "-1"], true, None),
+        (&["-C", "-1"], false, Some(ShortFormat::SingleColumn)),
+        (
+            &["-l", "--format=single-column"],
+            false,
+            Some(ShortFormat::SingleColumn),
+        ),
+    ];
+
+    for (options, long, short_format) in cases {
+        let args = try_parse_from_mode(
+            CompatMode::Gnu,
+            ["lsplus"].iter().chain(options),
         )
         .unwrap();
-        assert_eq!(explicit.short_format, Some(ShortFormat::Across));
+
+        assert_eq!(args.long, long, "{options:?}");
+        assert_eq!(args.short_format, short_format, "{options:?}");
+    }
+}
+
+#[test]
+fn test_parse_from_mode_owner_and_group_options_select_long_format() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        let numeric = try_parse_from_mode(mode, ["lsplus", "-n"]).unwrap();
+        assert!(numeric.long);
+        assert!(numeric.numeric_uid_gid);
+
+        let no_owner = try_parse_from_mode(mode, ["lsplus", "-g"]).unwrap();
+        assert!(no_owner.long);
+        assert!(no_owner.no_owner);
+        assert!(!no_owner.no_group);
+
+        let no_group = try_parse_from_mode(mode, ["lsplus", "-o"]).unwrap();
+        assert!(no_group.long);
+        assert!(no_group.no_group);
+
+        let no_group =
+            try_parse_from_mode(mode, ["lsplus", "--no-group"]).unwrap();
+        assert!(!no_group.long);
+        assert!(no_group.no_group);
+    }
+
+    let short =
+        try_parse_from_mode(CompatMode::Gnu, ["lsplus", "-go", "-C"]).unwrap();
+    assert!(!short.long);
+    assert!(short.no_owner && short.no_group);
+}
+
+#[test]
+fn test_parse_from_mode_format_accepts_gnu_word_aliases() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        let across =
+            try_parse_from_mode(mode, ["lsplus", "--format=horizontal"])
+                .unwrap();
+        assert_eq!(across.short_format, Some(ShortFormat::Across));
+
+        let long =
+            try_parse_from_mode(mode, ["lsplus", "--format=verbose"]).unwrap();
+        assert!(long.long);
     }
 }
 
@@ -461,6 +702,61 @@ fn test_parse_from_mode_accepts_icon_display_modes() {
     }
 }
 
+#[test]
+fn test_parse_from_mode_accepts_hyperlink_modes() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        for (args, expected) in [
+            (vec!["lsplus"], None),
+            (
+                vec!["lsplus", "--hyperlink"],
+                Some(HyperlinkDisplay::Always),
+            ),
+            (
+                vec!["lsplus", "--hyperlink=auto"],
+                Some(HyperlinkDisplay::Auto),
+            ),
+            (
+                vec!["lsplus", "--hyperlink=never"],
+                Some(HyperlinkDisplay::Never),
+            ),
+        ] {
+            let flags = try_parse_from_mode(mode, args).unwrap();
+
+            assert_eq!(flags.hyperlink, expected);
+        }
+    }
+}
+
+#[test]
+fn test_parse_from_mode_accepts_color_modes() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        for (args, expected, no_color) in [
+            (vec!["lsplus"], None, false),
+            (vec!["lsplus", "--color"], Some(ColorDisplay::Always), false),
+            (
+                vec!["lsplus", "--color=auto"],
+                Some(ColorDisplay::Auto),
+                false,
+            ),
+            (vec!["lsplus", "--color=always", "--no-color"], None, true),
+            (
+                vec!["lsplus", "--no-color", "--color=always"],
+                Some(ColorDisplay::Always),
+                false,
+            ),
+        ] {
+            let flags = try_parse_from_mode(mode, args).unwrap();
+
+            assert_eq!(flags.color, expected);
+            assert_eq!(flags.no_color, no_color);
+        }
+        assert!(
+            try_parse_from_mode(mode, ["lsplus", "--color=sometimes"])
+                .is_err()
+        );
+    }
+}
+
 #[test]
 fn test_parse_from_mode_rejects_conflicting_icon_options() {
     for mode in [CompatMode::Native, CompatMode::Gnu] {
@@ -478,7 +774,7 @@ fn test_parse_from_mode_rejects_conflicting_icon_options() {
 fn test_parse_from_mode_rejects_invalid_short_format() {
     for mode in [CompatMode::Native, CompatMode::Gnu] {
         let err =
-            try_parse_from_mode(mode, ["lsplus", "--format", "horizontal"])
+            try_parse_from_mode(mode, ["lsplus", "--format", "diagonal"])
                 .unwrap_err();
 
         assert_eq!(err.kind(), ErrorKind::InvalidValue);
@@ -509,6 +805,47 @@ fn test_parse_from_mode_accepts_prune_options() {
     }
 }
 
+#[test]
+fn test_parse_from_mode_from_stdin_requires_tree_and_no_paths() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        let args = try_parse_from_mode(
+            mode,
+            ["lsplus", "--tree", "--from-stdin", "-0"],
+        )
+        .unwrap();
+
+        assert!(args.from_stdin);
+        assert!(args.null_separated);
+
+        for invalid in [
+            &["lsplus", "--from-stdin"][..],
+            &["lsplus", "--tree", "-0"][..],
+            &["lsplus", "--tree", "--from-stdin", "src"][..],
+        ] {
+            assert!(
+                try_parse_from_mode(mode, invalid).is_err(),
+                "{invalid:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_parse_from_mode_max_entries_must_be_positive() {
+    for mode in [CompatMode::Native, CompatMode::Gnu] {
+        let args =
+            try_parse_from_mode(mode, ["lsplus", "--max-entries", "20"])
+                .unwrap();
+
+        assert_eq!(args.max_entries, Some(20));
+
+        let err = try_parse_from_mode(mode, ["lsplus", "--max-entries", "0"])
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    }
+}
+
 #[test]
 fn test_parse_from_mode_accepts_long_format_accent_disable_flags() {
     for mode in [CompatMode::Native, CompatMode::Gnu] {
@@ -588,7 +925,7 @@ fn test_parse_from_mode_rejects_invalid_attribute_display_mode() {
 
 #[test]
 fn test_parse_from_mode_gnu_rejects_conflicting_short_flags() {
-    for flag in ["-D", "-I", "-N", "-Z"] {
+    for flag in ["-I", "-Z"] {
         let err = try_parse_from_mode(CompatMode::Gnu, ["lsplus", flag])
             .unwrap_err();
 
@@ -631,12 +968,50 @@ fn test_parse_from_mode_gnu_help_omits_conflicting_short_flags() {
     assert!(help.contains("--indicator-style"));
     assert!(help.contains("--group-directories-first"));
     assert!(!help.contains("--slash-dirs"));
-    assert!(!help.contains("-D,"));
+    assert!(help.contains("-D, --dired"));
     assert!(!help.contains("-I,"));
-    assert!(!help.contains("-N,"));
+    assert!(help.contains("-N, --literal"));
     assert!(!help.contains("-Z,"));
 }
 
+#[test]
+fn test_parse_from_mode_quoting_options_follow_option_order() {
+    let args = try_parse_from_mode(
+        CompatMode::Gnu,
+        ["lsplus", "-Q", "--quoting-style=shell", "-b", "-N"],
+    )
+    .unwrap();
+
+    assert_eq!(args.quoting_style, Some(QuotingStyle::Literal));
+
+    let args = try_parse_from_mode(
+        CompatMode::Native,
+        ["lsplus", "--literal", "-Q", "--show-control-chars", "-q"],
+    )
+    .unwrap();
+
+    assert_eq!(args.quoting_style, Some(QuotingStyle::C));
+    assert_eq!(args.hide_control_chars, Some(true));
+
+    let args = try_parse_from_mode(
+        CompatMode::Native,
+        ["lsplus", "-q", "--show-control-chars", "-b"],
+    )
+    .unwrap();
+
+    assert_eq!(args.quoting_style, Some(QuotingStyle::Escape));
+    assert_eq!(args.hide_control_chars, Some(false));
+}
+
+#[test]
+fn test_parse_from_mode_native_keeps_short_n_for_no_color() {
+    let args =
+        try_parse_from_mode(CompatMode::Native, ["lsplus", "-N"]).unwrap();
+
+    assert!(args.no_color);
+    assert_eq!(args.quoting_style, None);
+}
+
 #[test]
 fn test_parse_from_mode_gnu_short_p_sets_slash() {
     let args = try_parse_from_mode(CompatMode::Gnu, ["lsplus", "-p"]).unwrap();
@@ -1,4 +1,4 @@
-use colored_text::{ColorMode, ColorizeConfig};
+use colored_text::{ColorDepthMode, ColorMode, ColorizeConfig};
 use std::sync::{Mutex, MutexGuard};
 
 use crate::Params;
@@ -7,6 +7,7 @@ static COLOR_MODE_LOCK: Mutex<()> = Mutex::new(());
 
 pub(crate) struct ColorModeGuard {
     previous: ColorMode,
+    previous_depth: ColorDepthMode,
     _lock: MutexGuard<'static, ()>,
 }
 
@@ -16,9 +17,11 @@ impl ColorModeGuard {
             .lock()
             .unwrap_or_else(std::sync::PoisonError::into_inner);
         let previous = ColorizeConfig::color_mode();
+        let previous_depth = ColorizeConfig::color_depth_mode();
         ColorizeConfig::set_color_mode(mode);
         Self {
             previous,
+            previous_depth,
             _lock: lock,
         }
     }
@@ -27,6 +30,7 @@ impl ColorModeGuard {
 impl Drop for ColorModeGuard {
     fn drop(&mut self) {
         ColorizeConfig::set_color_mode(self.previous);
+        ColorizeConfig::set_color_depth_mode(self.previous_depth);
     }
 }
 
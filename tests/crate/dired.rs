@@ -0,0 +1,96 @@
+use crate::common_tests::{fixed_time_params, with_color_output_enabled};
+use crate::render_tests::test_file_info;
+use crate::utils::dired::{DiredWriter, leading_escape_len};
+use crate::utils::file::colorize_name;
+use crate::utils::render::write_long_format;
+use crate::{NameStyle, Params, QuotingStyle};
+use colored_text::Colorize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+fn dired_params() -> Params {
+    Params {
+        dired: true,
+        long_format: true,
+        no_icons: true,
+        quoting_style: Some(QuotingStyle::Literal),
+        hide_control_chars: Some(false),
+        ..fixed_time_params()
+    }
+}
+
+/// Return the text each offset pair in a trailer line points at.
+fn marked_text<'a>(output: &'a str, label: &str) -> Vec<&'a str> {
+    let line = output
+        .lines()
+        .find(|line| line.starts_with(label))
+        .unwrap_or_else(|| panic!("missing {label} in {output:?}"));
+    let offsets = line[label.len()..]
+        .split_whitespace()
+        .map(|offset| offset.parse::<usize>().unwrap())
+        .collect::<Vec<_>>();
+    offsets
+        .chunks(2)
+        .map(|pair| &output[pair[0]..pair[1]])
+        .collect()
+}
+
+#[test]
+fn test_dired_offsets_point_at_names_in_written_bytes() {
+    with_color_output_enabled(|| {
+        let params = dired_params();
+        let plain = test_file_info("plain.txt", None, 3, SystemTime::now());
+        let mut directory = test_file_info("dir/", None, 0, SystemTime::now());
+        directory.display_name =
+            colorize_name("dir/", NameStyle::Directory, false);
+        directory.full_path = PathBuf::from("sub/dir");
+        let mut link = test_file_info("link", None, 0, SystemTime::now());
+        link.display_name = format!("{} -> target", "link".cyan());
+        let mut output = Vec::new();
+
+        let mut writer = DiredWriter::new(&mut output);
+        writer.write_header("sub").unwrap();
+        write_long_format(&mut writer, &[plain, directory, link], &params)
+            .unwrap();
+        writeln!(writer).unwrap();
+        writer.write_header("sub/dir").unwrap();
+        writer.finish(&params).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("  \x1b["));
+        assert_eq!(
+            marked_text(&output, "//DIRED//"),
+            ["plain.txt", "dir", "link"]
+        );
+        assert_eq!(marked_text(&output, "//SUBDIRED//"), ["sub", "sub/dir"]);
+        assert!(
+            output.ends_with("//DIRED-OPTIONS// --quoting-style=literal\n")
+        );
+    });
+}
+
+#[test]
+fn test_dired_writer_leaves_out_empty_trailers() {
+    let mut output = Vec::new();
+
+    DiredWriter::new(&mut output)
+        .finish(&Params {
+            quoting_style: Some(QuotingStyle::ShellEscape),
+            ..Params::default()
+        })
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "//DIRED-OPTIONS// --quoting-style=shell-escape\n"
+    );
+}
+
+#[test]
+fn test_leading_escape_len_skips_styling_only() {
+    assert_eq!(leading_escape_len("name"), 0);
+    assert_eq!(leading_escape_len("\x1b[34mname\x1b[0m"), 5);
+    assert_eq!(leading_escape_len("\x1b[1m\x1b[32mname"), 9);
+    assert_eq!(leading_escape_len("\x1b["), 0);
+}
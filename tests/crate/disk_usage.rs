@@ -0,0 +1,110 @@
+use crate::Params;
+use crate::structs::TotalSize;
+use crate::utils::disk_usage::{UsageCache, entry_size};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn size_of(path: &Path, total_size: TotalSize) -> u64 {
+    measure(path, total_size, &UsageCache::default())
+}
+
+fn measure(path: &Path, total_size: TotalSize, cache: &UsageCache) -> u64 {
+    let metadata = fs::symlink_metadata(path).unwrap();
+    entry_size(path, &metadata, total_size, &Params::default(), cache)
+}
+
+#[test]
+fn test_apparent_size_sums_nested_and_hidden_files() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    fs::write(temp_dir.path().join("top.txt"), "12345").unwrap();
+    fs::write(nested.join(".hidden"), "123").unwrap();
+    fs::write(nested.join("deep.txt"), "12").unwrap();
+
+    assert_eq!(size_of(temp_dir.path(), TotalSize::Apparent), 10);
+    assert_eq!(size_of(&nested, TotalSize::Apparent), 5);
+}
+
+#[test]
+fn test_file_size_is_measured_without_recursion() {
+    let temp_dir = tempdir().unwrap();
+    let file = temp_dir.path().join("file.txt");
+    fs::write(&file, "1234").unwrap();
+
+    assert_eq!(size_of(&file, TotalSize::Apparent), 4);
+}
+
+#[test]
+fn test_disk_size_includes_directory_allocation() {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir(temp_dir.path().join("empty")).unwrap();
+
+    assert!(
+        size_of(temp_dir.path(), TotalSize::Disk)
+            >= size_of(&temp_dir.path().join("empty"), TotalSize::Disk)
+    );
+    assert_eq!(size_of(temp_dir.path(), TotalSize::Apparent), 0);
+}
+
+#[test]
+fn test_hard_links_are_counted_once_per_total() {
+    let temp_dir = tempdir().unwrap();
+    let first = temp_dir.path().join("first");
+    let second = temp_dir.path().join("second");
+    fs::create_dir(&first).unwrap();
+    fs::create_dir(&second).unwrap();
+    fs::write(first.join("data"), "0123456789").unwrap();
+    fs::hard_link(first.join("data"), second.join("data")).unwrap();
+
+    assert_eq!(size_of(temp_dir.path(), TotalSize::Apparent), 10);
+    assert_eq!(size_of(&second, TotalSize::Apparent), 10);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlinks_are_measured_as_links() {
+    let temp_dir = tempdir().unwrap();
+    let target = temp_dir.path().join("target");
+    let listed = temp_dir.path().join("listed");
+    fs::create_dir(&target).unwrap();
+    fs::create_dir(&listed).unwrap();
+    fs::write(target.join("big.txt"), "x".repeat(100)).unwrap();
+    std::os::unix::fs::symlink(&target, listed.join("link")).unwrap();
+
+    let link_length = fs::symlink_metadata(listed.join("link")).unwrap().len();
+
+    assert_eq!(size_of(&listed, TotalSize::Apparent), link_length);
+}
+
+#[test]
+fn test_measured_subtrees_are_reused_without_double_counting_links() {
+    let temp_dir = tempdir().unwrap();
+    let first = temp_dir.path().join("first");
+    let second = temp_dir.path().join("first/second");
+    fs::create_dir_all(&second).unwrap();
+    fs::write(first.join("data"), "0123456789").unwrap();
+    fs::hard_link(first.join("data"), second.join("data")).unwrap();
+    fs::write(second.join("own"), "123").unwrap();
+
+    let cache = UsageCache::default();
+
+    assert_eq!(measure(&second, TotalSize::Apparent, &cache), 13);
+    assert_eq!(measure(&first, TotalSize::Apparent, &cache), 13);
+    assert_eq!(measure(temp_dir.path(), TotalSize::Apparent, &cache), 13);
+}
+
+#[test]
+fn test_new_usage_cache_sees_changes_below_unchanged_directories() {
+    let temp_dir = tempdir().unwrap();
+    let deep = temp_dir.path().join("outer/deep");
+    fs::create_dir_all(&deep).unwrap();
+    fs::write(deep.join("data"), "123").unwrap();
+
+    assert_eq!(size_of(temp_dir.path(), TotalSize::Apparent), 3);
+
+    fs::write(deep.join("data"), "123456").unwrap();
+
+    assert_eq!(size_of(temp_dir.path(), TotalSize::Apparent), 6);
+}
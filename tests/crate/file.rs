@@ -1,5 +1,6 @@
 use crate::common_tests::with_color_output_enabled;
 use crate::platform::{EntryClassification, LongFormatFileType};
+use crate::utils::disk_usage::UsageCache;
 use crate::utils::file::DirectoryEntryData;
 use crate::utils::file::{
     append_file_info_for_names, check_display_name, collect_file_info,
@@ -38,6 +39,8 @@ fn basic_info(display_name: &str, full_path: PathBuf) -> FileInfo {
         name_style: NameStyle::Plain,
         dimmed: false,
         full_path,
+        synthetic: None,
+        mount_point: false,
     }
 }
 
@@ -326,6 +329,7 @@ fn test_no_sort_preserves_input_order() {
             sort: SortMode::None,
             ..Params::default()
         },
+        &UsageCache::default(),
     );
 
     let names: Vec<_> = entries
@@ -351,6 +355,7 @@ fn test_reverse_keeps_directories_grouped_first() {
             reverse: true,
             ..Params::default()
         },
+        &UsageCache::default(),
     );
 
     assert_eq!(names, ["zeta-dir", "alpha-dir", "zeta-file", "alpha-file"]);
@@ -400,8 +405,12 @@ fn test_collect_visible_file_names_handles_iterator_and_file_type_errors() {
         }),
     ];
 
-    let names =
-        collect_visible_file_names(Path::new("/tmp"), entries, &params);
+    let names = collect_visible_file_names(
+        Path::new("/tmp"),
+        entries,
+        &params,
+        &UsageCache::default(),
+    );
 
     assert_eq!(names[0], ".");
     assert_eq!(names[1], "..");
@@ -428,6 +437,7 @@ fn test_append_file_info_for_names_skips_missing_entries() {
         &file_names,
         &Params::default(),
         &mut gitignore_cache,
+        &UsageCache::default(),
     );
 
     assert_eq!(file_info.len(), 1);
@@ -446,6 +456,7 @@ fn test_append_file_info_for_names_handles_empty_input() {
         &[],
         &Params::default(),
         &mut gitignore_cache,
+        &UsageCache::default(),
     );
 
     assert!(file_info.is_empty());
@@ -500,6 +511,7 @@ fn test_collect_visible_file_names_handles_empty_entries() {
         Path::new("/tmp"),
         Vec::new(),
         &Params::default(),
+        &UsageCache::default(),
     );
 
     assert!(names.is_empty());
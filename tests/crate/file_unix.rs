@@ -5,6 +5,8 @@ use crate::platform::{
     LongFormatFileType, compare_entry_names, get_groupname, get_username,
     long_format_file_type, name_style_for_file_type,
 };
+use crate::structs::TotalSize;
+use crate::utils::disk_usage::UsageCache;
 use crate::utils::file::{
     DirectoryEntryData, check_display_name, collect_file_info,
     collect_file_names, collect_visible_file_names, create_file_info,
@@ -18,7 +20,7 @@ use std::ffi::OsString;
 use std::fs::{self, File};
 use std::io;
 use std::os::unix::ffi::OsStringExt;
-use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
 use std::os::unix::net::UnixListener;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -33,6 +35,23 @@ fn test_get_username_and_groupname_fall_back_to_ids() {
     assert_eq!(get_groupname(u32::MAX), u32::MAX.to_string());
 }
 
+#[test]
+fn test_create_file_info_shows_numeric_ids_without_lookups() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("owned.txt");
+    File::create(&path).unwrap();
+    let metadata = fs::metadata(&path).unwrap();
+    let params = Params {
+        numeric_uid_gid: true,
+        ..Params::default()
+    };
+
+    let info = create_file_info(&path, &params).unwrap();
+
+    assert_eq!(info.user, metadata.uid().to_string());
+    assert_eq!(info.group, metadata.gid().to_string());
+}
+
 #[test]
 fn test_unix_name_sort_groups_dotfile_before_matching_visible_name() {
     assert_eq!(
@@ -82,7 +101,8 @@ fn test_collect_visible_file_names_hides_dotfiles_after_metadata_failure() {
         collect_visible_file_names(
             Path::new("/tmp"),
             entries(),
-            &Params::default()
+            &Params::default(),
+            &UsageCache::default(),
         )
         .is_empty()
     );
@@ -94,6 +114,7 @@ fn test_collect_visible_file_names_hides_dotfiles_after_metadata_failure() {
                 almost_all: true,
                 ..Params::default()
             },
+            &UsageCache::default(),
         ),
         vec![String::from(".hidden")]
     );
@@ -122,6 +143,36 @@ fn test_collect_file_info_preserves_synthetic_dot_names() {
     });
 }
 
+#[test]
+fn test_collect_file_info_keeps_metadata_size_for_dot_entries_with_total_size()
+{
+    let temp_dir = tempdir().unwrap();
+    let subdir = temp_dir.path().join("subdir");
+    fs::create_dir(&subdir).unwrap();
+    fs::write(temp_dir.path().join("big.bin"), vec![0; 100_000]).unwrap();
+    fs::write(subdir.join("small.txt"), "123").unwrap();
+
+    let info = collect_file_info(
+        &subdir,
+        &Params {
+            show_all: true,
+            total_size: Some(TotalSize::Apparent),
+            ..Params::default()
+        },
+    )
+    .unwrap();
+    let size_of = |name: &str| {
+        info.iter()
+            .find(|entry| entry.short_name == name)
+            .unwrap()
+            .size
+    };
+
+    assert_eq!(size_of(".."), fs::metadata(temp_dir.path()).unwrap().len());
+    assert_eq!(size_of("."), fs::metadata(&subdir).unwrap().len());
+    assert_eq!(size_of("small.txt"), 3);
+}
+
 #[test]
 fn test_create_file_info_handles_regular_files_symlinks_and_special_cases() {
     let temp_dir = tempdir().unwrap();
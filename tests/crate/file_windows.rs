@@ -9,6 +9,7 @@ use crate::platform::{
     parse_pathext, reparse_file_type, validate_params,
 };
 use crate::structs::{AttributeDisplay, PermissionDisplay};
+use crate::utils::disk_usage::UsageCache;
 use crate::utils::file::{
     DirectoryEntryData, collect_visible_file_names, colorize_name,
     file_type_indicator_suffix_for_type, format_symlink_display_name_with_dim,
@@ -29,6 +30,8 @@ fn test_windows_layout_uses_native_columns() {
         permission_display: PermissionDisplay::Symbolic,
         include_size_unit: true,
         include_icon: true,
+        include_owner: true,
+        include_group: true,
     });
 
     assert_eq!(
@@ -51,6 +54,8 @@ fn test_windows_layout_omits_optional_columns() {
         permission_display: PermissionDisplay::None,
         include_size_unit: false,
         include_icon: false,
+        include_owner: true,
+        include_group: true,
     });
 
     assert_eq!(
@@ -529,7 +534,12 @@ fn test_windows_collection_filters_hidden_and_groups_directories() {
     };
 
     assert_eq!(
-        collect_visible_file_names(Path::new("listing"), entries(), &params),
+        collect_visible_file_names(
+            Path::new("listing"),
+            entries(),
+            &params,
+            &UsageCache::default(),
+        ),
         vec![String::from("directory"), String::from("visible")]
     );
     assert_eq!(
@@ -540,6 +550,7 @@ fn test_windows_collection_filters_hidden_and_groups_directories() {
                 show_all: true,
                 ..params
             },
+            &UsageCache::default(),
         ),
         vec![
             String::from("directory"),
@@ -564,7 +575,12 @@ fn test_windows_collection_handles_entry_and_classification_errors() {
     };
 
     assert_eq!(
-        collect_visible_file_names(Path::new("."), entries, &params),
+        collect_visible_file_names(
+            Path::new("."),
+            entries,
+            &params,
+            &UsageCache::default(),
+        ),
         vec![String::from("fallback")]
     );
 }
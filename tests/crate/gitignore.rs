@@ -1,7 +1,8 @@
 use crate::platform::normalize_path;
 use crate::utils::gitignore::{
-    GitignoreCache, collect_gitignore_files, find_git_paths_parts,
-    matcher_ignores_path, parse_commondir, parse_gitdir_file,
+    GitignoreCache, LspignoreCache, collect_gitignore_files,
+    find_git_paths_parts, matcher_ignores_path, parse_commondir,
+    parse_gitdir_file,
 };
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -266,3 +267,35 @@ fn test_find_git_paths_returns_none_for_malformed_gitdir_file() {
 
     assert_eq!(find_git_paths_parts(&repo_root), None);
 }
+
+#[test]
+fn test_lspignore_cache_prefers_nearest_rules_without_git_worktree() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let nested_dir = root.join("nested");
+    fs::create_dir(&nested_dir).unwrap();
+    fs::write(root.join(".lspignore"), "*.gen\n").unwrap();
+    fs::write(nested_dir.join(".lspignore"), "!keep.gen\n").unwrap();
+
+    let mut cache = LspignoreCache::default();
+
+    assert!(cache.is_ignored(&root.join("top.gen"), false));
+    assert!(cache.is_ignored(&nested_dir.join("drop.gen"), false));
+    assert!(!cache.is_ignored(&nested_dir.join("keep.gen"), false));
+    assert!(!cache.is_ignored(&nested_dir.join("main.rs"), false));
+}
+
+#[test]
+fn test_lspignore_cache_anchors_patterns_to_their_file_directory() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let nested_dir = root.join("nested");
+    fs::create_dir(&nested_dir).unwrap();
+    fs::write(root.join(".lspignore"), "/out/\n").unwrap();
+
+    let mut cache = LspignoreCache::default();
+
+    assert!(cache.is_ignored(&root.join("out"), true));
+    assert!(!cache.is_ignored(&root.join("out"), false));
+    assert!(!cache.is_ignored(&nested_dir.join("out"), true));
+}
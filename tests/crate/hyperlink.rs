@@ -0,0 +1,55 @@
+use crate::platform;
+use crate::structs::{HyperlinkDisplay, Params};
+use crate::utils::hyperlink::{file_url, link_name};
+use crate::utils::table::visible_width;
+use std::path::Path;
+
+fn link_params() -> Params {
+    Params {
+        hyperlink: HyperlinkDisplay::Always,
+        ..Params::default()
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_file_url_includes_host_and_percent_encodes_reserved_bytes() {
+    assert_eq!(
+        file_url(Path::new("/tmp/a b#%é.txt")),
+        format!("file://{}/tmp/a%20b%23%25%C3%A9.txt", platform::hostname())
+    );
+}
+
+#[test]
+fn test_file_url_makes_relative_paths_absolute() {
+    let url = file_url(Path::new("name.txt"));
+    let host = platform::hostname();
+
+    assert!(url.starts_with(&format!("file://{host}/")));
+    assert!(url.ends_with("/name.txt"));
+}
+
+#[test]
+fn test_link_name_wraps_text_in_zero_width_osc8_escapes() {
+    let linked = link_name("name.txt", Path::new("/name.txt"), &link_params());
+
+    assert!(linked.starts_with("\x1b]8;;file://"));
+    assert!(linked.contains("/name.txt\x1b\\name.txt"));
+    assert!(linked.ends_with("\x1b]8;;\x1b\\"));
+    assert_eq!(visible_width(&linked), "name.txt".len());
+}
+
+#[test]
+fn test_link_name_leaves_text_alone_unless_links_are_on() {
+    for hyperlink in [HyperlinkDisplay::Never, HyperlinkDisplay::Auto] {
+        let params = Params {
+            hyperlink,
+            ..Params::default()
+        };
+
+        assert_eq!(
+            link_name("name.txt", Path::new("/name.txt"), &params),
+            "name.txt"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::Params;
+use crate::QuotingStyle;
+use crate::utils::quoting::{quote, quote_name};
+
+#[test]
+fn test_quote_literal_prints_names_as_they_are() {
+    assert_eq!(quote("a b", QuotingStyle::Literal, false), "a b");
+    assert_eq!(quote("a\nb", QuotingStyle::Literal, false), "a\nb");
+    assert_eq!(quote("a\nb", QuotingStyle::Literal, true), "a?b");
+}
+
+#[test]
+fn test_quote_shell_only_quotes_names_that_need_it() {
+    assert_eq!(
+        quote("plain-name.txt", QuotingStyle::Shell, true),
+        "plain-name.txt"
+    );
+    assert_eq!(quote("a b", QuotingStyle::Shell, true), "'a b'");
+    assert_eq!(quote("it's", QuotingStyle::Shell, true), "\"it's\"");
+    assert_eq!(
+        quote("it's $HOME", QuotingStyle::Shell, true),
+        "'it'\\''s $HOME'"
+    );
+    assert_eq!(quote("~home", QuotingStyle::Shell, true), "'~home'");
+    assert_eq!(quote("a~b", QuotingStyle::Shell, true), "a~b");
+    assert_eq!(quote("a\tb", QuotingStyle::Shell, true), "'a?b'");
+    assert_eq!(quote("name", QuotingStyle::ShellAlways, true), "'name'");
+}
+
+#[test]
+fn test_quote_shell_escape_writes_control_characters_as_ansi_c_words() {
+    assert_eq!(quote("a b", QuotingStyle::ShellEscape, true), "'a b'");
+    assert_eq!(
+        quote("a\nb", QuotingStyle::ShellEscape, true),
+        "'a'$'\\n''b'"
+    );
+    assert_eq!(
+        quote("\u{1b}[31m", QuotingStyle::ShellEscape, false),
+        "$'\\033''[31m'"
+    );
+}
+
+#[test]
+fn test_quote_c_escape_and_locale_use_backslash_escapes() {
+    assert_eq!(
+        quote("say \"hi\"\n", QuotingStyle::C, true),
+        "\"say \\\"hi\\\"\\n\""
+    );
+    assert_eq!(
+        quote("a b\\c\t", QuotingStyle::Escape, true),
+        "a\\ b\\\\c\\t"
+    );
+    assert_eq!(quote("a b", QuotingStyle::Locale, true), "‘a b’");
+}
+
+#[test]
+fn test_quote_name_defaults_to_shell_escape_until_resolved() {
+    let mut params = Params::default();
+
+    assert_eq!(quote_name("a b", &params), "'a b'");
+
+    params.resolve_quoting_output(false);
+
+    assert_eq!(params.quoting_style, Some(QuotingStyle::Literal));
+    assert_eq!(params.hide_control_chars, Some(false));
+    assert_eq!(quote_name("a b", &params), "a b");
+}
@@ -5,14 +5,17 @@ use crate::common_tests::{
 };
 use crate::utils::icons::Icon;
 use crate::utils::render::{
-    SizeCellStyle, build_long_format_table,
-    build_long_format_table_with_name_prefixes, directory_header_text,
-    render_short_format_lines, render_short_single_column_lines,
-    resolve_short_format, size_style_for_color_level,
-    terminal_width_or_default,
+    SizeCellStyle, UsageName, UsageRow, build_long_format_table,
+    build_long_format_table_with_name_prefixes, build_usage_table,
+    directory_header_text, render_short_format, render_short_format_lines,
+    render_short_single_column_lines, resolve_short_format,
+    size_style_for_color_level, summary_text, terminal_width_or_default,
+    usage_bar,
 };
+use crate::utils::walk::WalkSummary;
 use crate::{
-    FileInfo, NameStyle, Params, ShortFormat, structs::PermissionDisplay,
+    FileInfo, HyperlinkDisplay, NameStyle, Params, ShortFormat,
+    structs::{PermissionDisplay, TreeStyle},
 };
 use colored_text::{ColorLevel, ColorMode, Colorize};
 use std::path::PathBuf;
@@ -42,6 +45,8 @@ pub(crate) fn test_file_info(
         name_style: NameStyle::Plain,
         dimmed: false,
         full_path: PathBuf::from(display_name),
+        synthetic: None,
+        mount_point: false,
     }
 }
 
@@ -229,6 +234,47 @@ fn test_build_long_format_table_with_name_prefixes_uses_same_alignment() {
     );
 }
 
+#[test]
+fn test_build_long_format_table_links_names_without_moving_columns() {
+    with_color_output_enabled(|| {
+        let mut link = test_file_info("link", None, 5, SystemTime::now());
+        link.display_name = format!("{} -> target", "link".cyan());
+        let files = [
+            test_file_info("plain.txt", None, 12, SystemTime::now()),
+            link,
+        ];
+        let params = Params {
+            hyperlink: HyperlinkDisplay::Always,
+            ..fixed_time_params()
+        };
+
+        let linked =
+            normalized_table(build_long_format_table(&files, &params));
+        let plain = normalized_table(build_long_format_table(
+            &files,
+            &fixed_time_params(),
+        ));
+
+        assert_eq!(strip_str(&linked), strip_str(&plain));
+        assert!(linked.contains("/plain.txt\x1b\\plain.txt\x1b]8;;\x1b\\"));
+        assert!(linked.contains("\x1b]8;;\x1b\\\x1b[0m -> target"));
+    });
+}
+
+#[test]
+fn test_render_short_single_column_lines_links_names() {
+    let files = [test_file_info("plain.txt", None, 12, SystemTime::now())];
+    let params = Params {
+        hyperlink: HyperlinkDisplay::Always,
+        ..Params::default()
+    };
+
+    let lines = render_short_single_column_lines(&files, &params);
+
+    assert!(lines[0].starts_with("\x1b]8;;file://"));
+    assert_eq!(strip_str(&lines[0]), "plain.txt");
+}
+
 #[test]
 fn test_build_long_format_table_colors_special_file_types() {
     with_color_output_enabled(|| {
@@ -1026,23 +1072,176 @@ fn test_render_short_single_column_lines_has_no_grid_padding() {
         .map(|name| test_file_info(name, None, 0, SystemTime::now()));
 
     assert_eq!(
-        render_short_single_column_lines(&files),
+        render_short_single_column_lines(&files, &Params::default()),
         vec![String::from("alpha"), String::from("beta")]
     );
 }
 
 #[test]
 fn test_resolve_short_format_uses_explicit_format_or_stdout_default() {
-    for format in [ShortFormat::Vertical, ShortFormat::Across] {
-        assert_eq!(resolve_short_format(true, Some(format)), Some(format));
-        assert_eq!(resolve_short_format(false, Some(format)), Some(format));
+    for format in [
+        ShortFormat::Vertical,
+        ShortFormat::Across,
+        ShortFormat::Commas,
+        ShortFormat::SingleColumn,
+    ] {
+        assert_eq!(resolve_short_format(true, Some(format)), format);
+        assert_eq!(resolve_short_format(false, Some(format)), format);
     }
 
+    assert_eq!(resolve_short_format(true, None), ShortFormat::Vertical);
+    assert_eq!(resolve_short_format(false, None), ShortFormat::SingleColumn);
+}
+
+#[test]
+fn test_render_short_format_lines_wraps_comma_separated_entries() {
+    let files = ["alpha", "beta", "gamma", "delta"]
+        .map(|name| test_file_info(name, None, 0, SystemTime::now()));
+
+    let rendered = normalized_lines(render_short_format_lines(
+        &files,
+        20,
+        ShortFormat::Commas,
+    ));
+
+    assert_eq!(strip_str(&rendered), "alpha, beta, gamma,\ndelta");
+}
+
+#[test]
+fn test_render_short_format_lines_joins_bare_names_with_commas() {
+    let files = ["a", "b", "c"]
+        .map(|name| test_file_info(name, None, 0, SystemTime::now()));
+    let icon_files = ["a", "b"].map(|name| {
+        test_file_info(name, Some(Icon::RustFile), 0, SystemTime::now())
+    });
+
+    let rendered = render_short_format_lines(&files, 80, ShortFormat::Commas);
+    let with_icons =
+        render_short_format_lines(&icon_files, 80, ShortFormat::Commas);
+
+    assert_eq!(rendered, ["a, b, c"]);
+    assert_eq!(with_icons, [format!("{0} a, {0} b", Icon::RustFile)]);
+}
+
+/// Replace tabs with the spaces a terminal with `tab_size` stops would show.
+fn expand_tabs(text: &str, tab_size: usize) -> String {
+    let mut expanded = String::new();
+    let mut column = 0;
+    for value in text.chars() {
+        match value {
+            '\t' => {
+                let spaces = tab_size - column % tab_size;
+                expanded.push_str(&" ".repeat(spaces));
+                column += spaces;
+            }
+            '\n' => {
+                expanded.push(value);
+                column = 0;
+            }
+            _ => {
+                expanded.push(value);
+                column += UnicodeWidthStr::width(value.to_string().as_str());
+            }
+        }
+    }
+    expanded
+}
+
+#[test]
+fn test_render_short_format_tabs_match_space_layout() {
+    let names = [
+        "alpha.txt",
+        "bravo.txt",
+        "charlie.txt",
+        "delta.txt",
+        "echo.txt",
+        "foxtrot.txt",
+        "golf.txt",
+    ];
+    for icon in [None, Some(Icon::RustFile)] {
+        let files =
+            names.map(|name| test_file_info(name, icon, 0, SystemTime::now()));
+        for format in [ShortFormat::Vertical, ShortFormat::Across] {
+            for width in [20, 37, 50, 80] {
+                let spaced = render_short_format(
+                    &files,
+                    width,
+                    format,
+                    &Params::default(),
+                );
+                for tab_size in [1, 3, 4, 8] {
+                    let params = Params {
+                        tabsize: Some(tab_size),
+                        ..Params::default()
+                    };
+                    let tabbed =
+                        render_short_format(&files, width, format, &params);
+
+                    assert_eq!(
+                        expand_tabs(&tabbed, tab_size),
+                        spaced,
+                        "{icon:?} {format:?} width {width} tabs {tab_size}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_short_output_marks_mount_points() {
+    let mut mount = test_file_info("mnt", None, 0, SystemTime::now());
+    mount.mount_point = true;
+    let files = [mount, test_file_info("plain", None, 0, SystemTime::now())];
+    let params = Params {
+        no_color: true,
+        ..Params::default()
+    };
+
+    let lines = render_short_single_column_lines(&files, &params);
+    let commas = render_short_format_lines(&files, 80, ShortFormat::Commas);
+
+    assert_eq!(
+        lines.iter().map(strip_str).collect::<Vec<_>>(),
+        ["mnt [Mount Point]", "plain"]
+    );
+    assert_eq!(strip_str(&commas[0]), "mnt [Mount Point], plain");
+}
+
+#[test]
+fn test_zero_output_ends_lines_with_nul() {
+    let files = ["alpha", "beta"]
+        .map(|name| test_file_info(name, None, 0, SystemTime::now()));
+    let params = Params {
+        zero: true,
+        ..fixed_time_params()
+    };
+
+    let lines = render_short_single_column_lines(&files, &params);
+    let table = build_long_format_table(&files, &params).to_string();
+
+    assert_eq!(lines, ["alpha", "beta"]);
+    assert_eq!(table.matches('\0').count(), 2);
+    assert!(table.ends_with("beta\0"));
+    assert!(!table.contains('\n'));
+}
+
+#[test]
+fn test_render_short_format_lines_prints_single_column_with_icons() {
+    let files = [
+        test_file_info("alpha", None, 0, SystemTime::now()),
+        test_file_info("main.rs", Some(Icon::RustFile), 0, SystemTime::now()),
+    ];
+
+    let rendered =
+        render_short_format_lines(&files, 200, ShortFormat::SingleColumn);
+
+    assert_eq!(rendered.len(), 2);
+    assert_eq!(strip_str(&rendered[0]), "alpha");
     assert_eq!(
-        resolve_short_format(true, None),
-        Some(ShortFormat::Vertical)
+        strip_str(&rendered[1]),
+        format!("{} main.rs", Icon::RustFile)
     );
-    assert_eq!(resolve_short_format(false, None), None);
 }
 
 #[test]
@@ -1065,12 +1264,105 @@ fn test_directory_header_text_keeps_plain_output_when_color_disabled() {
 #[test]
 fn test_terminal_width_or_default_uses_detected_width() {
     assert_eq!(
-        terminal_width_or_default(Some((Width(120), Height(40)))),
+        terminal_width_or_default(Some((Width(120), Height(40))), Some("60")),
         120
     );
 }
 
+#[test]
+fn test_terminal_width_or_default_uses_valid_columns_variable() {
+    assert_eq!(terminal_width_or_default(None, Some("60")), 60);
+    assert_eq!(terminal_width_or_default(None, Some("wide")), 80);
+}
+
 #[test]
 fn test_terminal_width_or_default_falls_back_to_80() {
-    assert_eq!(terminal_width_or_default(None), 80);
+    assert_eq!(terminal_width_or_default(None, None), 80);
+}
+
+#[test]
+fn test_summary_text_counts_entries_like_tree() {
+    let summary = WalkSummary {
+        directories: 1,
+        files: 2,
+        bytes: 1,
+        ..WalkSummary::default()
+    };
+
+    assert_eq!(
+        summary_text(&summary, &Params::default()),
+        "1 directory, 2 files, 1 byte"
+    );
+}
+
+#[test]
+fn test_summary_text_shows_pruned_symlinks_errors_and_scaled_size() {
+    let summary = WalkSummary {
+        directories: 4,
+        files: 3,
+        symlinks: 2,
+        bytes: 2048,
+        pruned: 1,
+        errors: 1,
+        cycles: 0,
+    };
+    let params = Params {
+        human_readable: true,
+        ..Params::default()
+    };
+
+    assert_eq!(
+        summary_text(&summary, &params),
+        "4 directories (1 pruned), 3 files, 2 symlinks, 2K, 1 error"
+    );
+}
+
+#[test]
+fn test_usage_bar_fills_in_proportion_to_share() {
+    assert_eq!(
+        usage_bar(0.5, TreeStyle::Compact),
+        format!("{}{}", "█".repeat(10), "░".repeat(10))
+    );
+    assert_eq!(usage_bar(1.0, TreeStyle::Ascii), "#".repeat(20));
+    assert_eq!(usage_bar(0.0, TreeStyle::Ascii), ".".repeat(20));
+}
+
+#[test]
+fn test_build_usage_table_aligns_others_row_with_icon_rows() {
+    let info = test_file_info(
+        "example.rs",
+        Some(Icon::RustFile),
+        90,
+        SystemTime::now(),
+    );
+    let rows = [
+        UsageRow {
+            name: UsageName::Entry(Box::new(info)),
+            name_prefix: String::from("├── "),
+            size: 90,
+            share: 0.9,
+        },
+        UsageRow {
+            name: UsageName::Others(3),
+            name_prefix: String::from("└── "),
+            size: 10,
+            share: 0.1,
+        },
+    ];
+    let params = Params {
+        no_color: true,
+        ..Params::default()
+    };
+
+    let rendered = normalized_table(build_usage_table(&rows, &params));
+    let lines = rendered.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+        visible_column_start(lines[0], "├── "),
+        visible_column_start(lines[1], "└── ")
+    );
+    assert_eq!(
+        visible_column_start(lines[0], "├── "),
+        visible_column_end(lines[0], &Icon::RustFile.to_string()) + 2
+    );
 }
@@ -323,6 +323,38 @@ fn test_build_long_format_table_header_omits_disabled_columns() {
     assert!(header.contains("Name"));
 }
 
+#[test]
+fn test_build_long_format_table_omits_owner_and_group_columns() {
+    let info = test_file_info("plain.txt", None, 12, SystemTime::now());
+    let params = Params {
+        header: true,
+        no_icons: true,
+        no_owner: true,
+        ..Params::default()
+    };
+
+    let rendered = normalized_table(build_long_format_table(
+        std::slice::from_ref(&info),
+        &params,
+    ));
+    let header = strip_str(&rendered).lines().next().unwrap().to_string();
+
+    assert!(!header.contains("User"));
+    assert!(header.contains("Group"));
+
+    let params = Params {
+        no_group: true,
+        ..params
+    };
+    let rendered = normalized_table(build_long_format_table(&[info], &params));
+    let header = strip_str(&rendered).lines().next().unwrap().to_string();
+
+    assert!(!header.contains("User"));
+    assert!(!header.contains("Group"));
+    assert!(header.contains("Links"));
+    assert!(header.contains("Size"));
+}
+
 #[test]
 fn test_build_long_format_table_header_uses_column_alignment() {
     let mut info = test_file_info(
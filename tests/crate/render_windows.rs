@@ -25,6 +25,8 @@ fn windows_file_info() -> FileInfo {
         name_style: NameStyle::Junction,
         dimmed: false,
         full_path: PathBuf::from("junction"),
+        synthetic: None,
+        mount_point: false,
     }
 }
 
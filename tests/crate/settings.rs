@@ -4,15 +4,15 @@ use crate::settings::config_path_from_home;
 #[cfg(unix)]
 use crate::settings::load_config;
 use crate::settings::{
-    StartupConfig, load_config_from_path, load_startup_config_from,
-    resolve_config_path,
+    StartupConfig, apply_color_env, load_config_from_path,
+    load_startup_config_from, resolve_config_path,
 };
+use crate::{ColorDisplay, HyperlinkDisplay, Params, ShortFormat};
 #[cfg(unix)]
 use crate::{
     IndicatorStyle,
-    structs::{AttributeDisplay, PermissionDisplay},
+    structs::{AttributeDisplay, Dereference, PermissionDisplay, TreeStyle},
 };
-use crate::{Params, ShortFormat};
 use std::ffi::OsString;
 use std::fs;
 use std::path::PathBuf;
@@ -106,6 +106,24 @@ fn test_load_config_reads_across_short_format() {
     assert_eq!(params.short_format, Some(ShortFormat::Across));
 }
 
+#[test]
+fn test_load_config_reads_commas_and_single_column_short_formats() {
+    let temp_dir = tempdir().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+
+    for (value, expected) in [
+        ("commas", ShortFormat::Commas),
+        ("single-column", ShortFormat::SingleColumn),
+    ] {
+        fs::write(&config_path, format!("short_format = \"{value}\"\n"))
+            .unwrap();
+
+        let params = load_config_from_path(Some(config_path.clone()));
+
+        assert_eq!(params.short_format, Some(expected));
+    }
+}
+
 #[test]
 fn test_load_config_reads_icon_display() {
     let temp_dir = tempdir().unwrap();
@@ -184,6 +202,30 @@ fn test_load_config_reads_boolean_settings_from_home_config() {
                 size_colors: false,
                 gitignore: true,
                 fuzzy_time: true,
+                lspignore: true,
+                dereference: Dereference::Never,
+                one_file_system: false,
+                threads: 0,
+                tree_style: TreeStyle::Compact,
+                summary: false,
+                total_size: None,
+                usage: false,
+                collapse: false,
+                prune_empty: false,
+                dirs_only: false,
+                max_entries: None,
+                quoting_style: None,
+                hide_control_chars: None,
+                numeric_uid_gid: false,
+                no_owner: false,
+                no_group: false,
+                directory: false,
+                dired: false,
+                hyperlink: HyperlinkDisplay::Never,
+                zero: false,
+                width: None,
+                tabsize: None,
+                color: ColorDisplay::Auto,
             }
         );
     });
@@ -303,6 +345,29 @@ fn test_load_startup_config_rejects_invalid_env_mode() {
     assert!(err.contains("bogus"));
 }
 
+#[test]
+fn test_color_env_overrides_config_color() {
+    let mut params = Params {
+        no_color: true,
+        ..Params::default()
+    };
+
+    apply_color_env(&mut params, None).unwrap();
+
+    assert!(params.no_color);
+
+    apply_color_env(&mut params, Some("Always")).unwrap();
+
+    assert_eq!(params.color, ColorDisplay::Always);
+    assert!(!params.no_color);
+
+    let err = apply_color_env(&mut params, Some("sometimes")).unwrap_err();
+
+    assert!(err.contains("LSP_COLOR"));
+    assert!(err.contains("sometimes"));
+    assert_eq!(params.color, ColorDisplay::Always);
+}
+
 #[test]
 fn test_load_startup_config_rejects_invalid_config_mode() {
     let temp_dir = tempdir().unwrap();
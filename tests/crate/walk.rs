@@ -0,0 +1,417 @@
+use crate::Params;
+use crate::utils::disk_usage::UsageCache;
+use crate::utils::walk::{
+    DirectoryReader, ElidedEntries, WalkEvent, WalkOptions, Walker,
+    elide_entries,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+/// Flatten walk events into comparable strings relative to `root`.
+fn describe_walk(
+    root: &Path,
+    params: &Params,
+    options: WalkOptions,
+) -> Vec<String> {
+    Walker::new(
+        root,
+        params,
+        options,
+        DirectoryReader::new(params, &UsageCache::default()),
+    )
+    .map(|event| match event {
+        WalkEvent::Enter(directory) => {
+            let mut entries = directory
+                .entries
+                .iter()
+                .map(|entry| {
+                    let name = entry
+                        .chain
+                        .iter()
+                        .chain([&entry.name])
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if entry.descend {
+                        format!("{name}/")
+                    } else {
+                        name
+                    }
+                })
+                .collect::<Vec<_>>();
+            if let Some(elided) = directory.elided {
+                entries.push(format!("+{}", elided.count));
+            }
+            let entries = entries.join(" ");
+            let path = directory.path.strip_prefix(root).unwrap();
+            format!("enter {} [{entries}]", path.display())
+        }
+        WalkEvent::Leave => String::from("leave"),
+        WalkEvent::DirectoryError(error) | WalkEvent::EntryError(error) => {
+            format!("error {}", error.path.display())
+        }
+    })
+    .collect()
+}
+
+fn create_walk_fixture() -> tempfile::TempDir {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("alpha/nested")).unwrap();
+    fs::create_dir(root.join("beta")).unwrap();
+    fs::write(root.join("alpha/nested/deep.txt"), "deep").unwrap();
+    fs::write(root.join("alpha/file.txt"), "file").unwrap();
+    fs::write(root.join("top.txt"), "top").unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_walker_reports_directories_depth_first() {
+    let temp_dir = create_walk_fixture();
+    let params = Params::default();
+    let options = WalkOptions {
+        max_depth: None,
+        root_dot_entries: false,
+        collapse_chains: false,
+        max_entries: None,
+        directories_only: false,
+    };
+
+    assert_eq!(
+        describe_walk(temp_dir.path(), &params, options),
+        vec![
+            "enter  [alpha/ beta/ top.txt]",
+            "enter alpha [file.txt nested/]",
+            "enter alpha/nested [deep.txt]",
+            "leave",
+            "leave",
+            "enter beta []",
+            "leave",
+            "leave",
+        ]
+    );
+}
+
+#[test]
+fn test_walker_applies_the_same_depth_limit_for_recursive_and_tree() {
+    let temp_dir = create_walk_fixture();
+    let params = Params {
+        tree_level: 2,
+        recursive_level: Some(2),
+        ..Params::default()
+    };
+
+    let recursive = describe_walk(
+        temp_dir.path(),
+        &params,
+        WalkOptions::recursive(&params),
+    );
+    let tree =
+        describe_walk(temp_dir.path(), &params, WalkOptions::tree(&params));
+
+    assert_eq!(recursive, tree);
+    assert_eq!(
+        tree,
+        vec![
+            "enter  [alpha/ beta/ top.txt]",
+            "enter alpha [file.txt nested]",
+            "leave",
+            "enter beta []",
+            "leave",
+            "leave",
+        ]
+    );
+}
+
+#[test]
+fn test_walker_lists_pruned_directories_without_descending() {
+    let temp_dir = create_walk_fixture();
+    let params = Params {
+        prune_dirs: vec![String::from("alpha")],
+        ..Params::default()
+    };
+    let options = WalkOptions {
+        max_depth: None,
+        root_dot_entries: false,
+        collapse_chains: false,
+        max_entries: None,
+        directories_only: false,
+    };
+
+    assert_eq!(
+        describe_walk(temp_dir.path(), &params, options),
+        vec![
+            "enter  [alpha beta/ top.txt]",
+            "enter beta []",
+            "leave",
+            "leave"
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_walker_keeps_dot_entries_only_in_the_root_directory() {
+    let temp_dir = create_walk_fixture();
+    let params = Params {
+        show_all: true,
+        recursive_level: Some(2),
+        ..Params::default()
+    };
+
+    let events = describe_walk(
+        temp_dir.path(),
+        &params,
+        WalkOptions::recursive(&params),
+    );
+
+    assert_eq!(events[0], "enter  [. .. alpha/ beta/ top.txt]");
+    assert_eq!(events[1], "enter alpha [file.txt nested]");
+}
+
+#[test]
+fn test_walker_reports_unreadable_root_as_root_error() {
+    let temp_dir = tempdir().unwrap();
+    let missing = temp_dir.path().join("missing");
+    let params = Params::default();
+
+    let events = Walker::new(
+        &missing,
+        &params,
+        WalkOptions::tree(&params),
+        DirectoryReader::new(&params, &UsageCache::default()),
+    )
+    .collect::<Vec<_>>();
+
+    assert_eq!(events.len(), 1);
+    assert!(matches!(
+        &events[0],
+        WalkEvent::DirectoryError(error) if error.root && error.path == missing
+    ));
+}
+
+#[test]
+fn test_walker_output_does_not_depend_on_thread_count() {
+    let temp_dir = create_walk_fixture();
+    for index in 0..8 {
+        let branch = temp_dir.path().join(format!("branch{index}"));
+        fs::create_dir_all(branch.join("leaf")).unwrap();
+        fs::write(branch.join("leaf/file.txt"), "leaf").unwrap();
+    }
+    let options = WalkOptions {
+        max_depth: None,
+        root_dot_entries: false,
+        collapse_chains: false,
+        max_entries: None,
+        directories_only: false,
+    };
+    let serial = Params {
+        threads: 1,
+        ..Params::default()
+    };
+    let parallel = Params {
+        threads: 4,
+        ..Params::default()
+    };
+
+    assert_eq!(
+        describe_walk(temp_dir.path(), &parallel, options),
+        describe_walk(temp_dir.path(), &serial, options)
+    );
+}
+
+#[test]
+fn test_shared_read_pool_matches_serial_walks_after_an_abandoned_walk() {
+    let temp_dir = create_walk_fixture();
+    // More directories than the read-ahead window of two threads.
+    for index in 0..24 {
+        let branch = temp_dir.path().join(format!("branch{index:02}"));
+        fs::create_dir_all(branch.join("leaf")).unwrap();
+        fs::write(branch.join("leaf/file.txt"), "leaf").unwrap();
+    }
+    let options = WalkOptions {
+        max_depth: None,
+        root_dot_entries: false,
+        collapse_chains: false,
+        max_entries: None,
+        directories_only: false,
+    };
+    let serial = Params {
+        threads: 1,
+        ..Params::default()
+    };
+    let parallel = Params {
+        threads: 2,
+        ..Params::default()
+    };
+    let mut reader = DirectoryReader::new(&parallel, &UsageCache::default());
+
+    let abandoned =
+        Walker::new(temp_dir.path(), &parallel, options, &mut reader)
+            .take(3)
+            .count();
+    let first =
+        Walker::new(temp_dir.path(), &parallel, options, &mut reader).count();
+    let second = Walker::new(
+        temp_dir.path().join("branch03"),
+        &parallel,
+        options,
+        &mut reader,
+    )
+    .count();
+
+    assert_eq!(abandoned, 3);
+    assert_eq!(
+        first,
+        describe_walk(temp_dir.path(), &serial, options).len()
+    );
+    assert_eq!(
+        second,
+        describe_walk(&temp_dir.path().join("branch03"), &serial, options)
+            .len()
+    );
+}
+
+#[test]
+fn test_walker_collapses_single_child_directory_chains() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/main/java/app")).unwrap();
+    fs::create_dir(root.join("src/main/java/app/model")).unwrap();
+    fs::write(root.join("src/main/java/app/Main.java"), "main").unwrap();
+    fs::create_dir_all(root.join("docs/guide")).unwrap();
+    fs::write(root.join("docs/index.md"), "index").unwrap();
+    let params = Params::default();
+    let options = WalkOptions {
+        max_depth: Some(2),
+        root_dot_entries: false,
+        collapse_chains: true,
+        max_entries: None,
+        directories_only: false,
+    };
+
+    assert_eq!(
+        describe_walk(root, &params, options),
+        vec![
+            "enter  [docs/ src/main/java/app/]",
+            "enter docs [guide index.md]",
+            "leave",
+            "enter src/main/java/app [Main.java model]",
+            "leave",
+            "leave",
+        ]
+    );
+}
+
+#[test]
+fn test_walker_ends_collapsed_chains_at_pruned_directories() {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir_all(temp_dir.path().join("outer/target/debug")).unwrap();
+    let params = Params {
+        prune_dirs: vec![String::from("target")],
+        ..Params::default()
+    };
+    let options = WalkOptions {
+        max_depth: None,
+        root_dot_entries: false,
+        collapse_chains: true,
+        max_entries: None,
+        directories_only: false,
+    };
+
+    assert_eq!(
+        describe_walk(temp_dir.path(), &params, options),
+        vec!["enter  [outer/target]", "leave"]
+    );
+}
+
+#[test]
+fn test_walker_elides_entries_past_max_entries() {
+    let temp_dir = create_walk_fixture();
+    let params = Params::default();
+    let options = WalkOptions {
+        max_depth: None,
+        root_dot_entries: false,
+        collapse_chains: false,
+        max_entries: Some(1),
+        directories_only: false,
+    };
+
+    assert_eq!(
+        describe_walk(temp_dir.path(), &params, options),
+        vec![
+            "enter  [alpha/ +2]",
+            "enter alpha [file.txt +1]",
+            "leave",
+            "leave",
+        ]
+    );
+}
+
+#[test]
+fn test_walker_lists_only_directories_but_counts_every_entry() {
+    let temp_dir = create_walk_fixture();
+    let params = Params::default();
+    let options = WalkOptions {
+        max_depth: None,
+        root_dot_entries: false,
+        collapse_chains: false,
+        max_entries: None,
+        directories_only: true,
+    };
+
+    let counts = Walker::new(
+        temp_dir.path(),
+        &params,
+        options,
+        DirectoryReader::new(&params, &UsageCache::default()),
+    )
+    .filter_map(|event| match event {
+        WalkEvent::Enter(directory) => Some((
+            directory.entries.len(),
+            directory.counts.files,
+            directory.counts.directories,
+        )),
+        _ => None,
+    })
+    .collect::<Vec<_>>();
+
+    assert_eq!(counts, [(2, 1, 2), (1, 1, 1), (0, 1, 0), (0, 0, 0)]);
+}
+
+#[test]
+fn test_elide_entries_sums_left_out_sizes() {
+    let temp_dir = tempdir().unwrap();
+    for (name, contents) in [("a", "1"), ("b", "22"), ("c", "333")] {
+        fs::write(temp_dir.path().join(name), contents).unwrap();
+    }
+    let params = Params::default();
+    let mut entries = Walker::new(
+        temp_dir.path(),
+        &params,
+        WalkOptions::recursive(&params),
+        DirectoryReader::new(&params, &UsageCache::default()),
+    )
+    .find_map(|event| match event {
+        WalkEvent::Enter(directory) => Some(directory.entries),
+        _ => None,
+    })
+    .unwrap();
+    entries.retain(|entry| !matches!(entry.name.as_str(), "." | ".."));
+
+    let elided =
+        elide_entries(&mut entries, Some(1), &params, &UsageCache::default())
+            .unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(elided, ElidedEntries { count: 2, bytes: 5 });
+    assert_eq!(
+        elide_entries(&mut entries, Some(1), &params, &UsageCache::default()),
+        None
+    );
+    assert_eq!(
+        elide_entries(&mut entries, None, &params, &UsageCache::default()),
+        None
+    );
+}
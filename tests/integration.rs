@@ -1,6 +1,8 @@
 use assert_cmd::Command;
 use filetime::FileTime;
-use lsplus::settings::{COMPAT_MODE_ENV_VAR, CONFIG_FILE_ENV_VAR};
+use lsplus::settings::{
+    COLOR_ENV_VAR, COMPAT_MODE_ENV_VAR, CONFIG_FILE_ENV_VAR,
+};
 use lsplus::utils::icons::Icon;
 use std::fs;
 use std::process::Stdio;
@@ -326,6 +328,29 @@ fn test_gnu_no_sort_all_and_long_format_follow_option_order() {
     );
 }
 
+#[test]
+fn test_gnu_format_selectors_follow_option_order() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join("entry.txt"), "entry").unwrap();
+
+    let short = run_sort_command(
+        temp_dir.path(),
+        "gnu",
+        &["-l", "--format=single-column"],
+    );
+    assert_eq!(short, "entry.txt\n");
+
+    for options in [["-1", "-l"], ["-l", "-1"]] {
+        let long = run_sort_command(temp_dir.path(), "gnu", &options);
+        assert!(long.trim_start().starts_with('-'), "{long}");
+        assert!(line_has_name(long.trim_end(), "entry.txt"), "{long}");
+    }
+
+    let tree =
+        run_sort_command(temp_dir.path(), "gnu", &["--tree", "-l", "-1"]);
+    assert!(tree.trim_start().starts_with('-'), "{tree}");
+}
+
 #[test]
 fn test_directory_grouping_selectors_render_expected_order() {
     let temp_dir = tempdir().unwrap();
@@ -374,6 +399,115 @@ fn test_recursive_and_tree_output_use_selected_sort() {
     }
 }
 
+#[test]
+fn test_directory_lists_operands_without_recursing() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    fs::write(nested.join("child.txt"), "child").unwrap();
+
+    for mode in ["native", "gnu"] {
+        let mut cmd = command_with_home(temp_dir.path());
+        cmd.current_dir(temp_dir.path())
+            .env(COMPAT_MODE_ENV_VAR, mode)
+            .args(["-ldR", "nested", "--no-icons", "--no-color"]);
+        let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+        assert_eq!(stdout.lines().count(), 1);
+        assert!(stdout.trim_start().starts_with('d'));
+        assert!(line_has_name(stdout.trim_end(), "nested"));
+        assert!(!stdout.contains("child.txt"));
+    }
+}
+
+#[test]
+fn test_dired_offsets_match_recursive_output_bytes() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir_all(nested.join("deeper")).unwrap();
+    fs::write(temp_dir.path().join("top file.txt"), "top").unwrap();
+    fs::write(nested.join("child.txt"), "child").unwrap();
+
+    let mut cmd = command_with_home(temp_dir.path());
+    cmd.current_dir(temp_dir.path()).args([
+        "-R",
+        "--dired",
+        "--quoting-style=literal",
+        "--no-icons",
+        "--no-color",
+    ]);
+    let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+    let marked = |label: &str| {
+        let line = stdout
+            .lines()
+            .find(|line| line.starts_with(label))
+            .unwrap_or_else(|| panic!("missing {label} in {stdout:?}"));
+        let offsets = line[label.len()..]
+            .split_whitespace()
+            .map(|offset| offset.parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+        offsets
+            .chunks(2)
+            .map(|pair| stdout[pair[0]..pair[1]].to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        marked("//DIRED//"),
+        ["nested", "top file.txt", "child.txt", "deeper"]
+    );
+    assert_eq!(
+        marked("//SUBDIRED//"),
+        [
+            ".",
+            "nested",
+            &format!("nested{}deeper", std::path::MAIN_SEPARATOR)
+        ]
+    );
+    assert!(stdout.starts_with("  .:\n  d"));
+    assert!(stdout.ends_with("//DIRED-OPTIONS// --quoting-style=literal\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_zero_terminates_entries_and_headers_with_nul() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    fs::write(temp_dir.path().join("two\nlines"), "a").unwrap();
+    fs::write(nested.join("child"), "b").unwrap();
+
+    let mut cmd = command_with_home(temp_dir.path());
+    cmd.current_dir(temp_dir.path()).args(["--zero", "-R", "."]);
+    let (stdout, _stderr) = run_and_capture_raw(&mut cmd);
+
+    let records = stdout.split_terminator('\0').collect::<Vec<_>>();
+    assert_eq!(
+        records,
+        ["nested", "two\nlines", "", "nested:", "child"],
+        "{stdout:?}"
+    );
+}
+
+#[test]
+fn test_hyperlinks_follow_stdout_when_auto() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join("a b.txt"), "a").unwrap();
+
+    for (value, linked) in [("auto", false), ("always", true)] {
+        let mut cmd = command_with_home(temp_dir.path());
+        cmd.current_dir(temp_dir.path()).args([
+            &format!("--hyperlink={value}"),
+            "-1",
+            "--no-icons",
+        ]);
+        let (stdout, _stderr) = run_and_capture_raw(&mut cmd);
+
+        assert_eq!(stdout.contains("\x1b]8;;file://"), linked);
+        assert_eq!(stdout.contains("/a%20b.txt\x1b\\"), linked);
+    }
+}
+
 #[test]
 fn test_config_file() {
     // Create a temporary directory and config file
@@ -540,6 +674,85 @@ fn test_mixed_file_and_directory_paths_show_file_first() {
     assert!(header_position < child_position);
 }
 
+#[test]
+fn test_recursive_and_tree_summary_counts_visible_entries() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir_all(nested.join("deeper")).unwrap();
+    fs::write(temp_dir.path().join("root.txt"), "root").unwrap();
+    fs::write(nested.join("deep.txt"), "deep").unwrap();
+    fs::write(nested.join("deeper/hidden.txt"), "hidden").unwrap();
+
+    for traversal in ["--recursive", "--tree"] {
+        let mut cmd = Command::cargo_bin("lsp").unwrap();
+        cmd.args([traversal, "--level=2", "--summary", "--no-icons"])
+            .arg(temp_dir.path());
+        let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+        assert!(
+            stdout.ends_with("\n\n2 directories, 2 files, 8 bytes\n"),
+            "{traversal}: {stdout}"
+        );
+    }
+}
+
+#[test]
+fn test_tree_from_stdin_renders_listed_and_virtual_paths() {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir(temp_dir.path().join("src")).unwrap();
+    fs::write(temp_dir.path().join("src/main.rs"), "main").unwrap();
+
+    for (separator, extra_args) in [("\n", &[][..]), ("\0", &["-0"][..])] {
+        let mut cmd = Command::cargo_bin("lsp").unwrap();
+        cmd.current_dir(temp_dir.path())
+            .args(["--tree", "--from-stdin", "--summary", "--no-icons"])
+            .args(extra_args)
+            .write_stdin(format!(
+                "src/main.rs{separator}docs/guide.md{separator}"
+            ));
+        let (stdout, _stderr) = run_and_capture(&mut cmd);
+        let lines = stdout.lines().collect::<Vec<_>>();
+
+        assert!(lines[0].ends_with(" docs"), "{stdout}");
+        assert!(lines[0].contains('?'), "{stdout}");
+        assert!(lines[1].ends_with(" └── guide.md"), "{stdout}");
+        assert!(lines[2].ends_with(" src"), "{stdout}");
+        assert!(!lines[2].contains('?'), "{stdout}");
+        assert!(lines[3].ends_with(" └── main.rs"), "{stdout}");
+        assert!(
+            stdout.ends_with("\n\n2 directories, 2 files, 4 bytes\n"),
+            "{stdout}"
+        );
+    }
+}
+
+#[test]
+fn test_quoting_style_applies_to_names_and_headers() {
+    let temp_dir = tempdir().unwrap();
+    let spaced = temp_dir.path().join("my dir");
+    fs::create_dir(&spaced).unwrap();
+    fs::write(spaced.join("a file.txt"), "a").unwrap();
+
+    let mut cmd = Command::cargo_bin("lsp").unwrap();
+    cmd.current_dir(temp_dir.path()).args([
+        "-R",
+        "--no-icons",
+        "--quoting-style=shell",
+    ]);
+    let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+    assert!(stdout.contains("'my dir'"), "{stdout}");
+    assert!(stdout.contains("'my dir':\n"), "{stdout}");
+    assert!(stdout.contains("'a file.txt'"), "{stdout}");
+
+    let mut cmd = Command::cargo_bin("lsp").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .args(["--no-icons", "my dir"]);
+    let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+    assert_eq!(stdout.trim_end(), "a file.txt");
+}
+
 #[test]
 fn test_recursive_lists_nested_directory_headers() {
     let temp_dir = tempdir().unwrap();
@@ -1136,6 +1349,35 @@ fn test_no_color_flag_keeps_short_output_plain() {
     assert!(stdout.contains("plain.txt"));
 }
 
+#[test]
+fn test_color_always_styles_redirected_output() {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir(temp_dir.path().join("folder")).unwrap();
+    let colored = |args: &[&str], color_env: Option<&str>| {
+        let mut cmd = command_with_home(temp_dir.path());
+        for name in ["NO_COLOR", "FORCE_COLOR", "CLICOLOR", "CLICOLOR_FORCE"] {
+            cmd.env_remove(name);
+        }
+        if let Some(value) = color_env {
+            cmd.env(COLOR_ENV_VAR, value);
+        }
+        cmd.env("TERM", "xterm")
+            .arg("--no-icons")
+            .args(args)
+            .arg(temp_dir.path());
+        let (stdout, _stderr) = run_and_capture_raw(&mut cmd);
+        has_ansi(&stdout)
+    };
+
+    assert!(!colored(&[], None));
+    assert!(colored(&["--color=always"], None));
+    assert!(colored(&["--color"], None));
+    assert!(colored(&["-l", "--color=always"], None));
+    assert!(colored(&[], Some("always")));
+    assert!(!colored(&["--color=auto"], Some("always")));
+    assert!(!colored(&["--no-color"], Some("always")));
+}
+
 #[test]
 fn test_no_color_config_keeps_long_output_plain() {
     let temp_dir = tempdir().unwrap();
@@ -1221,6 +1463,46 @@ fn test_vertical_short_format_options_force_grid_when_redirected() {
     assert!(lines.iter().all(|line| *line == line.trim_end()));
 }
 
+#[test]
+fn test_width_option_and_columns_variable_set_grid_width() {
+    let temp_dir = tempdir().unwrap();
+    write_short_grid_fixture(temp_dir.path());
+    let grid_lines = |args: &[&str]| {
+        let mut cmd = command_with_home(temp_dir.path());
+        cmd.env("COLUMNS", "50")
+            .args(["-C", "--no-icons", "--no-color"])
+            .args(args)
+            .arg(temp_dir.path());
+        let (stdout, _stderr) = run_and_capture_raw(&mut cmd);
+        stdout.lines().map(str::to_owned).collect::<Vec<_>>()
+    };
+
+    assert_eq!(grid_lines(&[]).len(), 3);
+    assert_eq!(grid_lines(&["-w", "0"]).len(), 1);
+
+    let tabbed = grid_lines(&["--width=50", "-T", "4"]);
+    assert_eq!(tabbed.len(), 3);
+    assert!(tabbed[0].contains('\t'), "{tabbed:?}");
+    assert!(grid_lines(&[]).iter().all(|line| !line.contains('\t')));
+}
+
+#[test]
+fn test_commas_short_format_wraps_redirected_output_at_80_columns() {
+    let temp_dir = tempdir().unwrap();
+    write_short_grid_fixture(temp_dir.path());
+
+    let mut cmd = command_with_home(temp_dir.path());
+    cmd.args(["-m", "--no-icons", "--no-color"])
+        .arg(temp_dir.path());
+    let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+    assert_eq!(
+        stdout,
+        "alpha-000000000.txt, bravo-000000000.txt, charlie-000000000.txt,\n\
+         delta-000000000.txt, echo-000000000.txt, foxtrot-000000000.txt\n"
+    );
+}
+
 #[test]
 fn test_across_short_format_options_force_grid_when_redirected() {
     let temp_dir = tempdir().unwrap();
@@ -1547,13 +1829,17 @@ fn test_gitignore_flag_honors_global_excludes() {
 }
 
 #[test]
-fn test_gnu_compat_mode_from_env_rejects_conflicting_short_flag() {
+fn test_gnu_compat_mode_from_env_maps_short_d_to_dired() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join("alpha.txt"), "alpha").unwrap();
+
     let mut cmd = Command::cargo_bin("lsp").unwrap();
     cmd.env("LSP_COMPAT_MODE", "gnu")
         .arg("-D")
-        .assert()
-        .failure()
-        .stderr(predicates::str::contains("unexpected argument '-D'"));
+        .arg(temp_dir.path());
+    let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+    assert!(stdout.contains("//DIRED//"));
 }
 
 #[test]
@@ -1578,7 +1864,7 @@ fn test_gnu_compat_mode_from_env_rejects_fuzzy_time_short_flag() {
 
 #[test]
 #[cfg(unix)]
-fn test_gnu_compat_mode_from_config_rejects_conflicting_short_flag() {
+fn test_gnu_compat_mode_from_config_maps_short_d_to_dired() {
     let temp_dir = tempdir().unwrap();
     let config_dir = temp_dir.path().join(".config").join("lsplus");
 
@@ -1586,11 +1872,13 @@ fn test_gnu_compat_mode_from_config_rejects_conflicting_short_flag() {
     fs::write(config_dir.join("config.toml"), "compat_mode = \"gnu\"\n")
         .unwrap();
 
+    fs::write(temp_dir.path().join("alpha.txt"), "alpha").unwrap();
+
     let mut cmd = command_with_home(temp_dir.path());
-    cmd.arg("-N")
-        .assert()
-        .failure()
-        .stderr(predicates::str::contains("unexpected argument '-N'"));
+    cmd.arg("-D").arg(temp_dir.path());
+    let (stdout, _stderr) = run_and_capture(&mut cmd);
+
+    assert!(stdout.contains("//DIRED//"));
 }
 
 #[test]
@@ -1659,9 +1947,9 @@ fn test_gnu_compat_mode_help_omits_conflicting_short_flags() {
     );
     assert!(stdout.contains("--group-directories-first"));
     assert!(!stdout.contains("--slash-dirs"));
-    assert!(!stdout.contains("-D,"));
+    assert!(stdout.contains("-D, --dired"));
     assert!(!stdout.contains("-I,"));
-    assert!(!stdout.contains("-N,"));
+    assert!(stdout.contains("-N, --literal"));
     assert!(!stdout.contains("-Z,"));
 }
 
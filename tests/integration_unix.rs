@@ -246,6 +246,30 @@ fn test_broken_symlink_argument_long_format() {
         .stdout(predicates::str::contains("broken_link"));
 }
 
+#[test]
+fn test_recursive_dereference_reports_symlink_cycle() {
+    let temp_dir = tempdir().unwrap();
+    let nested = temp_dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    std::os::unix::fs::symlink(temp_dir.path(), nested.join("loop")).unwrap();
+
+    let mut cmd = command_with_home(temp_dir.path());
+    let output = cmd
+        .arg("-RL")
+        .arg("--no-icons")
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+
+    // Like GNU `ls`, the listing finishes but the cycle is serious trouble.
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = strip_str(String::from_utf8_lossy(&output.stdout));
+    let stderr = strip_str(String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("loop"));
+    assert!(stderr.contains("not listing already-listed directory"));
+    assert!(stderr.contains(&nested.join("loop").display().to_string()));
+}
+
 #[test]
 fn test_recursive_all_renders_synthetic_dot_names() {
     let temp_dir = tempdir().unwrap();
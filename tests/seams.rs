@@ -66,6 +66,33 @@ fn test_public_run_with_flags_accepts_missing_patterns() {
         gitignore: false,
         version: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     assert!(run_with_flags(flags).is_ok());
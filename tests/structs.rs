@@ -2,8 +2,11 @@ use config::Config;
 use lsplus::cli::Flags;
 use lsplus::utils::format::SizeScale;
 use lsplus::{
-    IconDisplay, IndicatorStyle, Params, ShortFormat, SortMode,
-    structs::{AttributeDisplay, PermissionDisplay},
+    ColorDisplay, HyperlinkDisplay, IconDisplay, IndicatorStyle, Params,
+    QuotingStyle, ShortFormat, SortMode,
+    structs::{
+        AttributeDisplay, Dereference, PermissionDisplay, TotalSize, TreeStyle,
+    },
 };
 use std::fs;
 use tempfile::tempdir;
@@ -37,6 +40,7 @@ fn test_default_params() {
     assert!(params.time_gradient);
     assert!(params.size_colors);
     assert!(!params.gitignore);
+    assert!(params.lspignore);
     assert!(!params.fuzzy_time);
 }
 
@@ -119,6 +123,30 @@ fn test_config_conversion() {
             size_colors: false,
             gitignore: true,
             fuzzy_time: true,
+            lspignore: true,
+            dereference: Dereference::Never,
+            one_file_system: false,
+            threads: 0,
+            tree_style: TreeStyle::Compact,
+            summary: false,
+            total_size: None,
+            usage: false,
+            collapse: false,
+            prune_empty: false,
+            dirs_only: false,
+            max_entries: None,
+            quoting_style: None,
+            hide_control_chars: None,
+            numeric_uid_gid: false,
+            no_owner: false,
+            no_group: false,
+            directory: false,
+            dired: false,
+            hyperlink: HyperlinkDisplay::Never,
+            zero: false,
+            width: None,
+            tabsize: None,
+            color: ColorDisplay::Auto,
         }
     );
 }
@@ -405,6 +433,30 @@ fn test_params_merge_prefers_true_from_either_source() {
         size_colors: true,
         gitignore: true,
         fuzzy_time: false,
+        lspignore: true,
+        dereference: Dereference::Never,
+        one_file_system: false,
+        threads: 0,
+        tree_style: TreeStyle::Compact,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: HyperlinkDisplay::Never,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: ColorDisplay::Auto,
     };
 
     let flags = Flags {
@@ -436,6 +488,33 @@ fn test_params_merge_prefers_true_from_either_source() {
         no_size_colors: true,
         gitignore: false,
         fuzzy_time: true,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     let params = Params::merge(&flags, &config);
@@ -472,6 +551,7 @@ fn test_params_merge_prefers_true_from_either_source() {
     assert!(!params.time_gradient);
     assert!(!params.size_colors);
     assert!(params.gitignore);
+    assert!(params.lspignore);
     assert!(params.fuzzy_time);
 }
 
@@ -506,6 +586,33 @@ fn test_params_merge_keeps_false_when_both_sources_are_false() {
         no_size_colors: false,
         gitignore: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     let params = Params::merge(&flags, &Params::default());
@@ -544,6 +651,33 @@ fn test_params_merge_header_prefers_true_from_either_source() {
         no_size_colors: false,
         gitignore: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
     let config = Params {
         header: true,
@@ -595,6 +729,33 @@ fn test_params_merge_uses_config_permissions_until_cli_overrides() {
         no_size_colors: false,
         gitignore: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     let params = Params::merge(&flags, &config);
@@ -673,6 +834,33 @@ fn test_params_merge_si_enables_decimal_human_readable_output() {
         no_size_colors: false,
         gitignore: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     let params = Params::merge(&flags, &Params::default());
@@ -718,6 +906,33 @@ fn test_params_merge_config_si_overrides_config_human_readable() {
         no_size_colors: false,
         gitignore: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     let params = Params::merge(&flags, &config);
@@ -762,6 +977,33 @@ fn test_params_merge_cli_prune_dirs_append_config_prune_dirs() {
         no_size_colors: false,
         gitignore: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     let params = Params::merge(&flags, &config);
@@ -813,6 +1055,33 @@ fn test_params_merge_deduplicates_prune_preset() {
         no_size_colors: false,
         gitignore: false,
         fuzzy_time: false,
+        no_lspignore: false,
+        dereference: false,
+        dereference_command_line: false,
+        one_file_system: false,
+        threads: None,
+        tree_style: None,
+        summary: false,
+        total_size: None,
+        usage: false,
+        collapse: false,
+        prune_empty: false,
+        dirs_only: false,
+        from_stdin: false,
+        null_separated: false,
+        max_entries: None,
+        quoting_style: None,
+        hide_control_chars: None,
+        numeric_uid_gid: false,
+        no_owner: false,
+        no_group: false,
+        directory: false,
+        dired: false,
+        hyperlink: None,
+        zero: false,
+        width: None,
+        tabsize: None,
+        color: None,
     };
 
     let params = Params::merge(&flags, &config);
@@ -820,3 +1089,382 @@ fn test_params_merge_deduplicates_prune_preset() {
     assert_eq!(params.prune_dirs.len(), 5);
     assert_eq!(params.prune_dirs, config.prune_dirs);
 }
+
+#[test]
+fn test_dereference_merges_config_with_cli_flags() {
+    let config = Config::builder()
+        .set_override("dereference", "command-line")
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert_eq!(params.dereference, Dereference::CommandLine);
+
+    let flags = Flags::parse_from(["lsplus", "-L"]);
+    assert_eq!(
+        Params::merge(&flags, &params).dereference,
+        Dereference::Always
+    );
+
+    let flags = Flags::parse_from(["lsplus", "-H"]);
+    let config = Params {
+        dereference: Dereference::Always,
+        ..Params::default()
+    };
+    assert_eq!(
+        Params::merge(&flags, &Params::default()).dereference,
+        Dereference::CommandLine
+    );
+    assert_eq!(
+        Params::merge(&flags, &config).dereference,
+        Dereference::Always
+    );
+}
+
+#[test]
+fn test_lspignore_can_be_disabled_by_config_or_flag() {
+    let config = Config::builder()
+        .set_override("lspignore", false)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert!(!params.lspignore);
+
+    let flags = Flags::parse_from(["lsplus", "--no-lspignore"]);
+
+    assert!(!Params::merge(&flags, &Params::default()).lspignore);
+}
+
+#[test]
+fn test_usage_defaults_to_apparent_totals_sorted_by_size() {
+    let flags = Flags::parse_from(["lsplus", "--usage"]);
+    let params = Params::merge(&flags, &Params::default());
+
+    assert!(params.usage);
+    assert_eq!(params.total_size, Some(TotalSize::Apparent));
+    assert_eq!(params.sort, SortMode::Size);
+
+    let flags =
+        Flags::parse_from(["lsplus", "--usage", "--total-size=disk", "-t"]);
+    let params = Params::merge(&flags, &Params::default());
+
+    assert_eq!(params.total_size, Some(TotalSize::Disk));
+    assert_eq!(params.sort, SortMode::Time);
+}
+
+#[test]
+fn test_total_size_flag_overrides_config() {
+    let config = Config::builder()
+        .set_override("total_size", "disk")
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert_eq!(params.total_size, Some(TotalSize::Disk));
+
+    let flags = Flags::parse_from(["lsplus", "--total-size"]);
+
+    assert_eq!(
+        Params::merge(&flags, &params).total_size,
+        Some(TotalSize::Apparent)
+    );
+}
+
+#[test]
+fn test_tree_style_flag_overrides_config() {
+    let config = Config::builder()
+        .set_override("tree_style", "classic")
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert_eq!(params.tree_style, TreeStyle::Classic);
+
+    let flags = Flags::parse_from(["lsplus", "--tree-style", "indent"]);
+
+    assert_eq!(Params::merge(&flags, &params).tree_style, TreeStyle::Indent);
+}
+
+#[test]
+fn test_quoting_style_flag_overrides_config() {
+    let config = Config::builder()
+        .set_override("quoting_style", "shell-always")
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+
+    assert_eq!(
+        Params::merge(&flags, &params).quoting_style,
+        Some(QuotingStyle::ShellAlways)
+    );
+
+    let flags = Flags::parse_from(["lsplus", "-Q"]);
+
+    assert_eq!(
+        Params::merge(&flags, &params).quoting_style,
+        Some(QuotingStyle::C)
+    );
+}
+
+#[test]
+fn test_numeric_ids_and_no_group_are_enabled_by_flag_or_config() {
+    let config = Config::builder()
+        .set_override("numeric_uid_gid", true)
+        .unwrap()
+        .set_override("no_group", true)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert!(merged.numeric_uid_gid);
+    assert!(merged.no_group);
+    assert!(!merged.long_format);
+
+    let flags = Flags::parse_from(["lsplus", "-nG", "-g"]);
+    let merged = Params::merge(&flags, &Params::default());
+
+    assert!(merged.numeric_uid_gid);
+    assert!(merged.no_owner);
+    assert!(merged.no_group);
+    assert!(merged.long_format);
+}
+
+#[test]
+fn test_hyperlink_flag_overrides_config() {
+    let config = Config::builder()
+        .set_override("hyperlink", "auto")
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert_eq!(merged.hyperlink, HyperlinkDisplay::Auto);
+
+    let flags = Flags::parse_from(["lsplus", "--hyperlink=never"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert_eq!(merged.hyperlink, HyperlinkDisplay::Never);
+    assert_eq!(Params::default().hyperlink, HyperlinkDisplay::Never);
+}
+
+#[test]
+fn test_color_flag_overrides_config() {
+    let config = Config::builder()
+        .set_override("no_color", true)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert!(merged.no_color);
+
+    let flags = Flags::parse_from(["lsplus", "--color=always"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert_eq!(merged.color, ColorDisplay::Always);
+    assert!(!merged.no_color);
+
+    let config = Config::builder()
+        .set_override("color", "never")
+        .unwrap()
+        .set_override("no_color", true)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert_eq!(params.color, ColorDisplay::Never);
+    assert!(!params.no_color);
+}
+
+#[test]
+fn test_width_and_tabsize_flags_override_config() {
+    let config = Config::builder()
+        .set_override("width", 100)
+        .unwrap()
+        .set_override("tabsize", 8)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert_eq!(merged.width, Some(100));
+    assert_eq!(merged.tabsize, Some(8));
+
+    let flags = Flags::parse_from(["lsplus", "-w", "0", "-T", "0"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert_eq!(merged.width, Some(0));
+    assert_eq!(merged.tabsize, Some(0));
+}
+
+#[test]
+fn test_dired_flag_implies_long_format() {
+    let flags = Flags::parse_from(["lsplus", "--dired"]);
+    let merged = Params::merge(&flags, &Params::default());
+
+    assert!(merged.dired);
+    assert!(merged.long_format);
+}
+
+#[test]
+fn test_zero_flag_defaults_to_plain_single_column_names() {
+    let config = Params {
+        icons: IconDisplay::Always,
+        quoting_style: Some(QuotingStyle::C),
+        short_format: Some(ShortFormat::Vertical),
+        ..Params::default()
+    };
+    let flags = Flags::parse_from(["lsplus", "--zero"]);
+    let merged = Params::merge(&flags, &config);
+
+    assert!(merged.zero);
+    assert!(merged.no_color);
+    assert!(merged.no_icons);
+    assert_eq!(merged.quoting_style, Some(QuotingStyle::Literal));
+    assert_eq!(merged.hide_control_chars, Some(false));
+    assert_eq!(merged.short_format, Some(ShortFormat::SingleColumn));
+
+    let flags = Flags::parse_from([
+        "lsplus",
+        "--zero",
+        "--icons=always",
+        "--quoting-style=c",
+        "-C",
+    ]);
+    let merged = Params::merge(&flags, &config);
+
+    assert!(!merged.no_icons);
+    assert_eq!(merged.quoting_style, Some(QuotingStyle::C));
+    assert_eq!(merged.short_format, Some(ShortFormat::Vertical));
+}
+
+#[test]
+fn test_zero_flag_keeps_configured_color() {
+    let config = Config::builder()
+        .set_override("color", "always")
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus", "--zero"]);
+    let merged = Params::merge(&flags, &params);
+
+    assert!(!merged.no_color);
+    assert_eq!(merged.color, ColorDisplay::Always);
+
+    let config = Params {
+        color: ColorDisplay::Never,
+        ..Params::default()
+    };
+
+    assert_eq!(Params::merge(&flags, &config).color, ColorDisplay::Never);
+}
+
+#[test]
+fn test_collapse_is_enabled_by_flag_or_config() {
+    let config = Config::builder()
+        .set_override("collapse", true)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+
+    assert!(Params::merge(&flags, &params).collapse);
+
+    let flags = Flags::parse_from(["lsplus", "--collapse"]);
+
+    assert!(Params::merge(&flags, &Params::default()).collapse);
+}
+
+#[test]
+fn test_prune_empty_is_enabled_by_flag_or_config() {
+    let config = Config::builder()
+        .set_override("prune_empty", true)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+
+    assert!(Params::merge(&flags, &params).prune_empty);
+
+    let flags = Flags::parse_from(["lsplus", "--prune-empty"]);
+
+    assert!(Params::merge(&flags, &Params::default()).prune_empty);
+}
+
+#[test]
+fn test_dirs_only_is_enabled_by_flag_or_config() {
+    let config = Config::builder()
+        .set_override("dirs_only", true)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+    let flags = Flags::parse_from(["lsplus"]);
+
+    assert!(Params::merge(&flags, &params).dirs_only);
+
+    let flags = Flags::parse_from(["lsplus", "--dirs-only"]);
+
+    assert!(Params::merge(&flags, &Params::default()).dirs_only);
+}
+
+#[test]
+fn test_max_entries_flag_overrides_config() {
+    let config = Config::builder()
+        .set_override("max_entries", 50)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert_eq!(params.max_entries, Some(50));
+
+    let flags = Flags::parse_from(["lsplus", "--max-entries", "5"]);
+
+    assert_eq!(Params::merge(&flags, &params).max_entries, Some(5));
+
+    let config = Config::builder()
+        .set_override("max_entries", 0)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert_eq!(params.max_entries, None);
+}
+
+#[test]
+fn test_threads_flag_overrides_config() {
+    let config = Config::builder()
+        .set_override("threads", 2)
+        .unwrap()
+        .build()
+        .unwrap();
+    let params: Params = config.into();
+
+    assert_eq!(params.threads, 2);
+
+    let flags = Flags::parse_from(["lsplus", "--threads", "1"]);
+
+    assert_eq!(Params::merge(&flags, &params).threads, 1);
+}